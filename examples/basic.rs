@@ -1,7 +1,7 @@
 use std::{cell::RefCell, rc::Rc};
 
 use autons::prelude::*;
-use doxa_selector::{route, DoxaSelect};
+use doxa_selector::{providers, route, Alliance, DiagnosticEntry, DoxaSelect, FieldSide, Pose};
 use vexide::prelude::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -77,23 +77,21 @@ impl doxa_selector::DoxaSelectInterface for DoxaSelectInterfaceImpl {
         })
         .detach();
     }
-    fn calibrating_calibrating(&self) -> std::rc::Rc<std::cell::RefCell<bool>> {
-        self.calibrating.clone()
+    fn calibrating_calibrating(&self) -> bool {
+        *self.calibrating.borrow()
     }
     fn diagnostics_enable(&self) -> bool {
         true
     }
-    fn diagnostics_diagnostics(&self) -> Vec<(String, String)> {
-        vec![
-            (
-                "Battery charge".to_string(),
-                format!("{:.0}%", vexide::battery::capacity() * 100.0),
-            ),
-            (
-                "Uptime".to_string(),
-                format!("{:.1} seconds", vexide::time::system_uptime().as_secs_f32()),
-            ),
-        ]
+    fn diagnostics_entries(&self) -> Vec<DiagnosticEntry> {
+        let mut entries = providers::battery();
+        entries.push(
+            DiagnosticEntry::number("Uptime", vexide::time::system_uptime().as_secs_f64())
+                .section("System")
+                .unit("s"),
+        );
+        entries.extend(providers::competition());
+        entries
     }
 }
 
@@ -101,28 +99,37 @@ impl doxa_selector::DoxaSelectInterface for DoxaSelectInterfaceImpl {
 async fn main(peripherals: Peripherals) {
     let robot = Robot {};
 
-    robot
-        .compete(DoxaSelect::new(
-            peripherals.display,
-            [
-                route!(Category::Category1, Robot::route_1),
-                route!(
-                    Category::Category2,
-                    Robot::route_2,
-                    "My very very long description for route 2."
-                ),
-                route!(Category::Category3, Robot::route_1),
-                route!(Category::Category4, Robot::route_2),
-                route!(Category::Category5, Robot::route_1),
-                route!(Category::Category6, Robot::route_2),
-                route!(Category::Category7, Robot::route_1),
-                route!(Category::Category8, Robot::route_2),
-                route!(Category::Category9, Robot::route_1),
-                route!(Category::Category10, Robot::route_2),
-                route!(Category::Category11, Robot::route_1),
-                route!(Category::Category12, Robot::route_2),
-            ],
-            DoxaSelectInterfaceImpl::default(),
-        ))
-        .await;
+    let selector = DoxaSelect::builder(
+        peripherals.display,
+        &[
+            route!(Category::Category1, Robot::route_1)
+                .with_path(
+                    Pose::new(-60.0, -36.0, 90.0),
+                    [Pose::new(-24.0, -36.0, 90.0), Pose::new(-24.0, 0.0, 0.0)],
+                )
+                .with_alliance(Alliance::Red)
+                .with_side(FieldSide::Left),
+            route!(
+                Category::Category2,
+                Robot::route_2,
+                "My very very long description for route 2."
+            ),
+            route!(Category::Category3, Robot::route_1),
+            route!(Category::Category4, Robot::route_2),
+            route!(Category::Category5, Robot::route_1),
+            route!(Category::Category6, Robot::route_2),
+            route!(Category::Category7, Robot::route_1),
+            route!(Category::Category8, Robot::route_2),
+            route!(Category::Category9, Robot::route_1),
+            route!(Category::Category10, Robot::route_2),
+            route!(Category::Category11, Robot::route_1),
+            route!(Category::Category12, Robot::route_2),
+        ],
+    )
+    .interface(DoxaSelectInterfaceImpl::default())
+    .controller(peripherals.primary_controller)
+    .build()
+    .unwrap();
+
+    robot.compete(selector).await;
 }
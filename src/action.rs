@@ -0,0 +1,52 @@
+/// Look of a [`QuickAction`]'s button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActionStyle {
+    #[default]
+    Normal,
+    /// Filled with the primary color, for the action used most
+    Primary,
+    /// Filled with the error color, for actions that move mechanisms or lose
+    /// state
+    Destructive,
+}
+
+/// A button in the bottom bar that runs code in the interface, such as
+/// "Calibrate IMU", "Zero lift" or "Reset odometry".
+///
+/// Actions are returned by [`DoxaSelectInterface::actions`] and run by
+/// [`DoxaSelectInterface::on_action`].
+///
+/// [`DoxaSelectInterface::actions`]: crate::DoxaSelectInterface::actions
+/// [`DoxaSelectInterface::on_action`]: crate::DoxaSelectInterface::on_action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuickAction {
+    pub label: String,
+    pub style: ActionStyle,
+    /// Whether the action can be run right now
+    ///
+    /// Disabled actions are shown greyed out and ignore taps.
+    pub enabled: bool,
+}
+
+impl QuickAction {
+    /// Creates an enabled action with the normal style.
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            style: ActionStyle::Normal,
+            enabled: true,
+        }
+    }
+
+    /// Sets the look of the action's button.
+    pub fn style(mut self, style: ActionStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sets whether the action can be run right now.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
@@ -0,0 +1,170 @@
+//! Team identity shown by the selector.
+
+/// Where an image is loaded from.
+///
+/// Images can be BMPs or [QOI](https://qoiformat.org) files, which are much
+/// smaller and keep uploads fast when embedded in the program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageSource {
+    /// An image file on the SD card
+    Path(String),
+    /// Image data embedded in the program, usually with [`include_bytes!`]
+    Bytes(&'static [u8]),
+    /// No image
+    None,
+}
+
+impl ImageSource {
+    /// Creates a source for an image file on the SD card.
+    pub fn path(path: impl Into<String>) -> Self {
+        Self::Path(path.into())
+    }
+}
+
+/// How an image is scaled and cropped to the 480x240 display when loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImageFit {
+    /// Keep the image as it is
+    Original,
+    /// Cut off the edges of images larger than the display
+    #[default]
+    Crop,
+    /// Scale the image to fit inside the display, keeping its aspect ratio
+    Contain,
+    /// Scale the image to cover the display, keeping its aspect ratio, and cut
+    /// off the edges that don't fit
+    Cover,
+}
+
+/// Corner of the screen the logo is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogoPosition {
+    TopLeading,
+    TopTrailing,
+    BottomLeading,
+    #[default]
+    BottomTrailing,
+}
+
+/// Team identity and images shown by the selector.
+///
+/// The team number and name are shown in the bottom bar, followed by the event
+/// name if there is one, so referees checking robots can tell whose brain
+/// they're looking at.
+///
+/// # Example
+///
+/// ```ignore
+/// let branding = Branding::new("1234A", "Example Robotics")
+///     .event("Worlds 2026")
+///     .logo(ImageSource::Bytes(include_bytes!("../assets/logo.qoi")))
+///     .selected_background(ImageSource::None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branding {
+    pub team_number: String,
+    pub team_name: String,
+    pub event_name: Option<String>,
+    pub logo: ImageSource,
+    pub selected_background: ImageSource,
+    pub logo_position: LogoPosition,
+    pub logo_fit: ImageFit,
+    pub background_fit: ImageFit,
+}
+
+impl Default for Branding {
+    /// DOXA Robotics' branding.
+    ///
+    /// The images are loaded from the SD card, or embedded in the program if
+    /// the `embedded-assets` feature is enabled. On the SD card, the BMP
+    /// images that older versions used are loaded if the QOI ones are missing.
+    fn default() -> Self {
+        #[cfg(feature = "embedded-assets")]
+        let (logo, selected_background) = (
+            ImageSource::Bytes(include_bytes!("../assets/logo-cropped.qoi")),
+            ImageSource::Bytes(include_bytes!("../assets/selected-background.qoi")),
+        );
+        #[cfg(not(feature = "embedded-assets"))]
+        let (logo, selected_background) = (
+            ImageSource::path("logo-cropped.qoi"),
+            ImageSource::path("selected-background.qoi"),
+        );
+
+        Self {
+            team_number: "99484A".to_string(),
+            team_name: "DOXA Robotics".to_string(),
+            event_name: None,
+            logo,
+            selected_background,
+            logo_position: LogoPosition::default(),
+            logo_fit: ImageFit::Crop,
+            background_fit: ImageFit::Cover,
+        }
+    }
+}
+
+impl Branding {
+    /// Creates branding for the given team with no images.
+    pub fn new(team_number: impl Into<String>, team_name: impl Into<String>) -> Self {
+        Self {
+            team_number: team_number.into(),
+            team_name: team_name.into(),
+            event_name: None,
+            logo: ImageSource::None,
+            selected_background: ImageSource::None,
+            logo_position: LogoPosition::default(),
+            logo_fit: ImageFit::Crop,
+            background_fit: ImageFit::Cover,
+        }
+    }
+
+    /// Sets the name of the event, shown after the team name.
+    pub fn event(mut self, name: impl Into<String>) -> Self {
+        self.event_name = Some(name.into());
+        self
+    }
+
+    /// Sets the logo drawn in a corner of every screen.
+    pub fn logo(mut self, source: ImageSource) -> Self {
+        self.logo = source;
+        self
+    }
+
+    /// Sets the background shown once a route is confirmed.
+    pub fn selected_background(mut self, source: ImageSource) -> Self {
+        self.selected_background = source;
+        self
+    }
+
+    /// Sets the corner of the screen the logo is drawn in.
+    pub fn logo_position(mut self, position: LogoPosition) -> Self {
+        self.logo_position = position;
+        self
+    }
+
+    /// Sets how the logo is scaled and cropped. Defaults to [`ImageFit::Crop`].
+    pub fn logo_fit(mut self, fit: ImageFit) -> Self {
+        self.logo_fit = fit;
+        self
+    }
+
+    /// Sets how the confirmed background is scaled and cropped. Defaults to
+    /// [`ImageFit::Cover`].
+    pub fn background_fit(mut self, fit: ImageFit) -> Self {
+        self.background_fit = fit;
+        self
+    }
+
+    /// Returns the text shown in the bottom bar.
+    pub(crate) fn footer(&self) -> String {
+        let team = [self.team_number.as_str(), self.team_name.as_str()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        match &self.event_name {
+            Some(event) => format!("{team} - {event}"),
+            None => team,
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use vexide::{controller::Controller, display::Display, task};
+
+use crate::{
+    branding::{Branding, ImageSource},
+    persist,
+    theme::Theme,
+    typography::Typography,
+    view, BuildError, Category, ChecklistItem, DoxaSelect, DoxaSelectInterface, ExternalState,
+    Route, RouteInfo,
+};
+
+/// Default target frames per second
+///
+/// The display can do 60 FPS, but to avoid unnecessary CPU usage, we limit to
+/// 30 FPS
+const DEFAULT_FPS: u32 = 30;
+
+/// Maximum frames per second the display can show
+const MAX_FPS: u32 = 60;
+
+/// Default time before we stop rendering due to inactivity
+///
+/// Must be at least as long as the longest animation
+const DEFAULT_INACTIVITY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Default height of the cards in the category and route grids
+const DEFAULT_CARD_HEIGHT: u32 = 56;
+
+/// Maximum number of columns in the category and route grids
+pub const MAX_GRID_COLUMNS: usize = 4;
+
+/// Options that affect the UI, set through [`DoxaSelectBuilder`].
+#[derive(Debug, Clone)]
+pub(crate) struct Config {
+    pub fps: u32,
+    pub inactivity_timeout: Duration,
+    pub card_height: u32,
+    /// Number of grid columns, or `None` to choose by label width
+    pub grid_columns: Option<usize>,
+    /// How often the diagnostics are re-polled from the interface, or `None`
+    /// to only poll when the diagnostics screen is opened or refreshed
+    pub diagnostics_interval: Option<Duration>,
+    /// Whether routes can't be confirmed until calibration has succeeded
+    pub require_calibration: bool,
+    pub branding: Branding,
+    pub theme: Theme,
+    pub typography: Typography,
+    pub field_image: ImageSource,
+    /// Pre-match checklist items that don't depend on the interface
+    pub checklist: Vec<ChecklistItem>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            fps: DEFAULT_FPS,
+            inactivity_timeout: DEFAULT_INACTIVITY_TIMEOUT,
+            card_height: DEFAULT_CARD_HEIGHT,
+            grid_columns: None,
+            diagnostics_interval: None,
+            require_calibration: false,
+            branding: Branding::default(),
+            theme: Theme::default(),
+            typography: Typography::default(),
+            field_image: ImageSource::None,
+            checklist: Vec::new(),
+        }
+    }
+}
+
+/// Interface used when none is given to the builder, which disables the
+/// optional calibration and diagnostics features.
+pub(crate) struct NoInterface;
+
+impl DoxaSelectInterface for NoInterface {}
+
+/// Builder for [`DoxaSelect`], created by [`DoxaSelect::builder`].
+///
+/// # Example
+///
+/// ```ignore
+/// let selector = DoxaSelect::builder(peripherals.display, &routes)
+///     .interface(MyInterface::default())
+///     .controller(peripherals.primary_controller)
+///     .branding(Branding::new("1234A", "Example Robotics"))
+///     .build()
+///     .unwrap();
+/// ```
+pub struct DoxaSelectBuilder<C: Category, R: 'static> {
+    display: Display,
+    routes: Vec<Route<C, R>>,
+    interface: Box<dyn DoxaSelectInterface>,
+    controller: Option<Controller>,
+    default_route: usize,
+    config: Config,
+}
+
+impl<C: Category, R> DoxaSelectBuilder<C, R> {
+    pub(crate) fn new(display: Display, routes: &[Route<C, R>]) -> Self {
+        Self {
+            display,
+            routes: routes.to_vec(),
+            interface: Box::new(NoInterface),
+            controller: None,
+            default_route: 0,
+            config: Config::default(),
+        }
+    }
+
+    /// Sets the interface used for calibration, diagnostics and selection hooks.
+    pub fn interface(mut self, interface: impl DoxaSelectInterface + 'static) -> Self {
+        self.interface = Box::new(interface);
+        self
+    }
+
+    /// Enables navigation with a controller.
+    ///
+    /// The D-pad moves the focus between items and then along the bottom bar,
+    /// A selects and confirms, and B goes back. On the diagnostics screen, up
+    /// and down change pages. The current screen is mirrored to the
+    /// controller's screen.
+    pub fn controller(mut self, controller: Controller) -> Self {
+        self.controller = Some(controller);
+        self
+    }
+
+    /// Sets the index of the route that is selected when there's no saved
+    /// selection to restore. Defaults to the first route.
+    pub fn default_route(mut self, index: usize) -> Self {
+        self.default_route = index;
+        self
+    }
+
+    /// Sets the target frames per second, up to 60. Defaults to 30 to avoid
+    /// unnecessary CPU usage.
+    pub fn fps(mut self, fps: u32) -> Self {
+        self.config.fps = fps;
+        self
+    }
+
+    /// Sets how long the UI keeps rendering after the last input. Defaults to
+    /// one second.
+    ///
+    /// This must be at least as long as the longest animation, otherwise
+    /// animations are cut off.
+    pub fn inactivity_timeout(mut self, timeout: Duration) -> Self {
+        self.config.inactivity_timeout = timeout;
+        self
+    }
+
+    /// Sets the height in pixels of the cards in the category and route grids.
+    pub fn card_height(mut self, height: u32) -> Self {
+        self.config.card_height = height;
+        self
+    }
+
+    /// Sets the number of columns in the category and route grids, from 1 to
+    /// [`MAX_GRID_COLUMNS`].
+    ///
+    /// By default, the number of columns is chosen for each grid so that the
+    /// longest label fits.
+    pub fn grid_columns(mut self, columns: usize) -> Self {
+        self.config.grid_columns = Some(columns);
+        self
+    }
+
+    /// Makes the diagnostics be re-polled from the interface at the given
+    /// interval, instead of only when the diagnostics screen is opened or
+    /// refreshed.
+    ///
+    /// Polling carries on while other screens are open, so the history behind
+    /// sparklines and charts has no gaps. It pauses outside the disabled and
+    /// driver control modes so it never takes time from autonomous.
+    pub fn live_diagnostics(mut self, interval: Duration) -> Self {
+        self.config.diagnostics_interval = Some(interval);
+        self
+    }
+
+    /// Sets whether routes can't be confirmed until calibration has
+    /// succeeded.
+    ///
+    /// Either way, the confirmation screen flags a robot that hasn't been
+    /// calibrated or whose calibration failed. This has no effect unless the
+    /// interface enables calibration.
+    pub fn require_calibration(mut self, required: bool) -> Self {
+        self.config.require_calibration = required;
+        self
+    }
+
+    /// Sets the team identity and images shown in the UI. Defaults to DOXA
+    /// Robotics' branding.
+    pub fn branding(mut self, branding: Branding) -> Self {
+        self.config.branding = branding;
+        self
+    }
+
+    /// Sets the colors of the UI. Defaults to DOXA Robotics' dark theme.
+    ///
+    /// Use [`Theme::from_seed`] to generate a theme from a brand color.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.config.theme = theme;
+        self
+    }
+
+    /// Sets the fonts and text sizes of the UI. Defaults to Montserrat.
+    pub fn typography(mut self, typography: Typography) -> Self {
+        self.config.typography = typography;
+        self
+    }
+
+    /// Sets the field image that route paths are drawn over. Defaults to plain
+    /// tiles.
+    ///
+    /// The image should show the whole field from above, from the drive
+    /// team's side. It's scaled down to fit the preview.
+    pub fn field_image(mut self, source: ImageSource) -> Self {
+        self.config.field_image = source;
+        self
+    }
+
+    /// Sets the pre-match checklist shown on the confirmation screen, such as
+    /// "Preloads loaded" or "Air tanks full".
+    ///
+    /// Routes can't be confirmed until every required item is ticked. The ticks
+    /// are cleared when autonomous starts, so the checklist is fresh for the
+    /// next match. Items that depend on the robot can be added by the
+    /// interface's `checklist` method. Long checklists are split into pages of
+    /// four items.
+    pub fn checklist(mut self, items: impl IntoIterator<Item = ChecklistItem>) -> Self {
+        self.config.checklist = items.into_iter().collect();
+        self
+    }
+
+    /// Validates the options and starts the selector.
+    pub fn build(self) -> Result<DoxaSelect<C, R>, BuildError> {
+        let Self {
+            display,
+            routes,
+            interface,
+            controller,
+            default_route,
+            config,
+        } = self;
+
+        if routes.is_empty() {
+            return Err(BuildError::NoRoutes);
+        }
+        if default_route >= routes.len() {
+            return Err(BuildError::DefaultRouteOutOfBounds {
+                index: default_route,
+                len: routes.len(),
+            });
+        }
+        if !(1..=MAX_FPS).contains(&config.fps) {
+            return Err(BuildError::InvalidFps(config.fps));
+        }
+        if config.inactivity_timeout.is_zero() {
+            return Err(BuildError::ZeroInactivityTimeout);
+        }
+        if config.card_height == 0 {
+            return Err(BuildError::ZeroCardHeight);
+        }
+        if config
+            .diagnostics_interval
+            .is_some_and(|interval| interval.is_zero())
+        {
+            return Err(BuildError::ZeroDiagnosticsInterval);
+        }
+        if let Some(columns) = config.grid_columns {
+            if !(1..=MAX_GRID_COLUMNS).contains(&columns) {
+                return Err(BuildError::InvalidGridColumns(columns));
+            }
+        }
+
+        let categories = crate::route::categories(&routes);
+
+        // Restore the previously confirmed route, if it still exists. It's
+        // confirmed again by the UI unless calibration or the checklist blocks
+        // it
+        let restored = persist::load().and_then(|saved| {
+            let index = routes.iter().position(|route| {
+                route.category.to_string() == saved.category && route.name == saved.name
+            });
+            if index.is_none() {
+                log::warn!("Saved selection {:?} doesn't match any route", saved);
+            }
+            index
+        });
+
+        let state = Rc::new(RefCell::new(ExternalState {
+            restored: restored.is_some(),
+            calibrating: interface.calibrating_enable() && interface.calibrating_calibrating(),
+            ..ExternalState::new(restored.unwrap_or(default_route))
+        }));
+
+        let routes_vec = routes.clone();
+        Ok(DoxaSelect {
+            state: state.clone(),
+            route_infos: routes
+                .iter()
+                .enumerate()
+                .map(|(index, route)| RouteInfo::new(index, route))
+                .collect(),
+            routes,
+            _task: task::spawn(async move {
+                view::run(
+                    display, controller, state, interface, config, routes_vec, categories,
+                )
+                .await;
+            }),
+        })
+    }
+}
@@ -0,0 +1,21 @@
+/// Outcome of a calibration, reported by
+/// [`DoxaSelectInterface::calibrating_result`].
+///
+/// [`DoxaSelectInterface::calibrating_result`]: crate::DoxaSelectInterface::calibrating_result
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CalibrationResult {
+    Success,
+    /// Calibration failed, so the robot isn't ready to run a route
+    Failed(String),
+    /// Calibration finished, but something may need checking
+    Warning(String),
+}
+
+impl CalibrationResult {
+    /// Whether a route can be confirmed after this calibration.
+    ///
+    /// Warnings don't block confirmation, but are still shown.
+    pub fn is_usable(&self) -> bool {
+        !matches!(self, Self::Failed(_))
+    }
+}
@@ -0,0 +1,58 @@
+/// An item on the pre-match checklist shown on the confirmation screen.
+///
+/// Items are either ticked by hand, such as "Preloads loaded", or checked by
+/// the robot, such as "IMU calibrated". Routes can't be confirmed until every
+/// required item is done.
+///
+/// Items are told apart by their label, which keeps a tick with its item when
+/// the interface adds or removes others, so labels should be unique.
+///
+/// # Example
+///
+/// ```ignore
+/// vec![
+///     ChecklistItem::manual("Preloads loaded"),
+///     ChecklistItem::manual("Air tanks full").optional(),
+///     ChecklistItem::automatic("IMU calibrated", !self.imu.is_calibrating()),
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+    pub label: String,
+    /// Whether the item must be done before a route can be confirmed
+    pub required: bool,
+    /// Whether the robot has checked the item, or `None` if it's ticked by
+    /// hand
+    pub done: Option<bool>,
+}
+
+impl ChecklistItem {
+    /// Creates a required item that the drive team ticks by tapping it.
+    pub fn manual(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            required: true,
+            done: None,
+        }
+    }
+
+    /// Creates a required item that is done when `done` is true.
+    ///
+    /// Return these from [`DoxaSelectInterface::checklist`] so that `done` is
+    /// re-evaluated every frame.
+    ///
+    /// [`DoxaSelectInterface::checklist`]: crate::DoxaSelectInterface::checklist
+    pub fn automatic(label: impl Into<String>, done: bool) -> Self {
+        Self {
+            label: label.into(),
+            required: true,
+            done: Some(done),
+        }
+    }
+
+    /// Makes the item optional, so it doesn't block confirmation.
+    pub fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+}
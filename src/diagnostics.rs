@@ -0,0 +1,265 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Number of samples kept for each numeric diagnostic
+pub(crate) const HISTORY_LEN: usize = 60;
+
+/// Digits shown after the decimal point of a numeric diagnostic, unless set
+/// with [`DiagnosticEntry::precision`]
+const DEFAULT_PRECISION: usize = 2;
+
+/// Identifies an entry across polls by its section and label.
+pub(crate) type DiagnosticKey = (Option<String>, String);
+
+/// How healthy the value of a [`DiagnosticEntry`] is, which colors its row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiagnosticStatus {
+    #[default]
+    Ok,
+    Warning,
+    Error,
+}
+
+/// Value of a [`DiagnosticEntry`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticValue {
+    /// Free-form text
+    Text(String),
+    /// A measurement, shown with a gauge bar if it has a range
+    Number {
+        value: f64,
+        unit: Option<String>,
+        /// Expected minimum and maximum of the value
+        range: Option<(f64, f64)>,
+        /// Digits shown after the decimal point
+        precision: usize,
+    },
+}
+
+/// A single row on the diagnostics screen.
+///
+/// # Example
+///
+/// ```ignore
+/// vec![
+///     DiagnosticEntry::number("Battery", 12.4)
+///         .section("Power")
+///         .unit("V")
+///         .range(11.0, 13.0)
+///         .precision(1),
+///     DiagnosticEntry::text("Left drive", "Disconnected")
+///         .section("Motors")
+///         .status(DiagnosticStatus::Error),
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagnosticEntry {
+    /// Heading that the entry is grouped under
+    ///
+    /// Consecutive entries with the same section are shown under one heading.
+    pub section: Option<String>,
+    pub label: String,
+    pub value: DiagnosticValue,
+    pub status: DiagnosticStatus,
+}
+
+impl DiagnosticEntry {
+    /// Creates an entry with a text value.
+    pub fn text(label: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            section: None,
+            label: label.into(),
+            value: DiagnosticValue::Text(value.into()),
+            status: DiagnosticStatus::Ok,
+        }
+    }
+
+    /// Creates an entry with a numeric value, shown to two decimal places
+    /// unless set otherwise with [`precision`](Self::precision).
+    pub fn number(label: impl Into<String>, value: f64) -> Self {
+        Self {
+            section: None,
+            label: label.into(),
+            value: DiagnosticValue::Number {
+                value,
+                unit: None,
+                range: None,
+                precision: DEFAULT_PRECISION,
+            },
+            status: DiagnosticStatus::Ok,
+        }
+    }
+
+    /// Sets the section the entry is grouped under.
+    pub fn section(mut self, section: impl Into<String>) -> Self {
+        self.section = Some(section.into());
+        self
+    }
+
+    /// Sets the status, which colors the entry's row.
+    pub fn status(mut self, status: DiagnosticStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Sets the unit shown after a numeric value. Has no effect on text values.
+    pub fn unit(mut self, unit: impl Into<String>) -> Self {
+        if let DiagnosticValue::Number { unit: u, .. } = &mut self.value {
+            *u = Some(unit.into());
+        }
+        self
+    }
+
+    /// Sets the expected range of a numeric value, which is shown as a gauge
+    /// bar. Has no effect on text values.
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        if let DiagnosticValue::Number { range, .. } = &mut self.value {
+            *range = Some((min, max));
+        }
+        self
+    }
+
+    /// Sets how many digits are shown after the decimal point of a numeric
+    /// value, including its chart's minimum, maximum and average. Has no effect
+    /// on text values.
+    pub fn precision(mut self, digits: usize) -> Self {
+        if let DiagnosticValue::Number { precision, .. } = &mut self.value {
+            *precision = digits;
+        }
+        self
+    }
+
+    pub(crate) fn key(&self) -> DiagnosticKey {
+        (self.section.clone(), self.label.clone())
+    }
+
+    /// Formats the value with its unit.
+    pub(crate) fn formatted_value(&self) -> String {
+        match &self.value {
+            DiagnosticValue::Text(text) => text.clone(),
+            DiagnosticValue::Number { value, .. } => self.format_number(*value),
+        }
+    }
+
+    /// Formats a number with the entry's precision and unit, if it has one.
+    pub(crate) fn format_number(&self, value: f64) -> String {
+        let (unit, precision) = match &self.value {
+            DiagnosticValue::Number {
+                unit, precision, ..
+            } => (unit.as_deref(), *precision),
+            DiagnosticValue::Text(_) => (None, DEFAULT_PRECISION),
+        };
+        match unit {
+            Some(unit) => format!("{value:.precision$} {unit}"),
+            None => format!("{value:.precision$}"),
+        }
+    }
+
+    /// How far the value is through its range, from 0 to 1, if it has one.
+    pub(crate) fn gauge(&self) -> Option<f32> {
+        match self.value {
+            DiagnosticValue::Number {
+                value,
+                range: Some((min, max)),
+                ..
+            } if max > min => Some(((value - min) / (max - min)).clamp(0.0, 1.0) as f32),
+            _ => None,
+        }
+    }
+}
+
+impl From<(String, String)> for DiagnosticEntry {
+    fn from((label, value): (String, String)) -> Self {
+        Self::text(label, value)
+    }
+}
+
+/// Recent values of the numeric entries, so that trends show on the
+/// diagnostics screen.
+///
+/// A sample is recorded every time the interface is polled.
+#[derive(Debug, Default)]
+pub(crate) struct DiagnosticHistory {
+    samples: HashMap<DiagnosticKey, VecDeque<f64>>,
+}
+
+impl DiagnosticHistory {
+    /// Records the current value of each numeric entry, dropping the oldest
+    /// sample once an entry has [`HISTORY_LEN`] of them.
+    ///
+    /// The history of entries that are no longer reported, or are no longer
+    /// numeric, is forgotten.
+    pub(crate) fn record(&mut self, entries: &[DiagnosticEntry]) {
+        self.samples.retain(|key, _| {
+            entries.iter().any(|entry| {
+                matches!(entry.value, DiagnosticValue::Number { .. }) && entry.key() == *key
+            })
+        });
+        for entry in entries {
+            if let DiagnosticValue::Number { value, .. } = entry.value {
+                let samples = self.samples.entry(entry.key()).or_default();
+                if samples.len() == HISTORY_LEN {
+                    samples.pop_front();
+                }
+                samples.push_back(value);
+            }
+        }
+    }
+
+    /// Samples of the entry, oldest first.
+    pub(crate) fn get(&self, entry: &DiagnosticEntry) -> Option<&VecDeque<f64>> {
+        self.samples.get(&entry.key())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_keeps_latest_samples() {
+        let mut history = DiagnosticHistory::default();
+        let entry = |value| DiagnosticEntry::number("Battery", value);
+        for value in 0..HISTORY_LEN + 5 {
+            history.record(&[entry(value as f64)]);
+        }
+        let samples = history.get(&entry(0.0)).unwrap();
+        assert_eq!(samples.len(), HISTORY_LEN);
+        assert_eq!(samples.front(), Some(&5.0));
+        assert_eq!(samples.back(), Some(&((HISTORY_LEN + 4) as f64)));
+    }
+
+    #[test]
+    fn history_forgets_unreported_entries() {
+        let mut history = DiagnosticHistory::default();
+        let battery = DiagnosticEntry::number("Battery", 12.4);
+        let lift = DiagnosticEntry::number("Lift", 40.0).section("Motors");
+        history.record(&[battery.clone(), lift.clone()]);
+        history.record(&[battery.clone()]);
+        assert_eq!(history.get(&battery).map(VecDeque::len), Some(2));
+        assert_eq!(history.get(&lift), None);
+
+        // An entry that turns into text has no history either
+        history.record(&[DiagnosticEntry::text("Battery", "Unplugged")]);
+        assert_eq!(history.get(&battery), None);
+    }
+
+    #[test]
+    fn numbers_use_entry_precision() {
+        let entry = DiagnosticEntry::number("Charge", 87.46).unit("%");
+        assert_eq!(entry.formatted_value(), "87.46 %");
+        assert_eq!(entry.clone().precision(0).formatted_value(), "87 %");
+        assert_eq!(
+            DiagnosticEntry::number("Temp", 41.25)
+                .precision(1)
+                .format_number(39.96),
+            "40.0"
+        );
+        // Text values ignore it
+        assert_eq!(
+            DiagnosticEntry::text("Port 1", "Motor")
+                .precision(1)
+                .formatted_value(),
+            "Motor"
+        );
+    }
+}
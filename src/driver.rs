@@ -1,8 +1,20 @@
+use std::time::{Duration, Instant};
+
 use embedded_touch::{traits::TouchInputDevice, Touch, TouchPoint};
+use vexide::controller::Controller;
 
 // Blend factor for filtering touch input
 const BLEND_FACTOR: f32 = 0.25;
 
+/// Minimum time between controller screen writes
+///
+/// Controller screen updates are sent over the radio link, which drops writes
+/// that are sent more often than this.
+const CONTROLLER_WRITE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Number of characters that fit on a line of the controller screen
+const CONTROLLER_LINE_WIDTH: usize = 19;
+
 pub struct DisplayTouchDriver<'a> {
     display: &'a mut vexide::display::Display,
     touch: Option<embedded_touch::Touch>,
@@ -75,3 +87,104 @@ impl TouchInputDevice for DisplayTouchDriver<'_> {
         }
     }
 }
+
+/// Navigation input from a V5 controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControllerAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// The A button
+    Select,
+    /// The B button
+    Back,
+}
+
+impl ControllerAction {
+    /// All actions, in the order their buttons are polled
+    const ALL: [Self; 6] = [
+        Self::Up,
+        Self::Down,
+        Self::Left,
+        Self::Right,
+        Self::Select,
+        Self::Back,
+    ];
+}
+
+/// Navigation input and screen mirroring for a V5 controller.
+pub struct ControllerDriver {
+    controller: Controller,
+    /// Whether each button in [`ControllerAction::ALL`] was held last poll
+    held: [bool; 6],
+    /// Lines currently shown on the controller screen
+    shown: [String; 3],
+    last_write: Instant,
+}
+
+impl ControllerDriver {
+    pub fn new(controller: Controller) -> Self {
+        Self {
+            controller,
+            held: [false; 6],
+            shown: Default::default(),
+            last_write: Instant::now(),
+        }
+    }
+
+    /// Returns the actions for buttons that were pressed since the last poll.
+    pub fn actions(&mut self) -> Vec<ControllerAction> {
+        let Ok(state) = self.controller.state() else {
+            // The controller is disconnected
+            return Vec::new();
+        };
+        let held = [
+            state.button_up.is_pressed(),
+            state.button_down.is_pressed(),
+            state.button_left.is_pressed(),
+            state.button_right.is_pressed(),
+            state.button_a.is_pressed(),
+            state.button_b.is_pressed(),
+        ];
+        // Only report buttons on the rising edge so holding a button doesn't
+        // repeat the action every frame
+        let actions = ControllerAction::ALL
+            .into_iter()
+            .zip(held.iter().zip(self.held.iter()))
+            .filter(|(_, (now, before))| **now && !**before)
+            .map(|(action, _)| action)
+            .collect();
+        self.held = held;
+        actions
+    }
+
+    /// Mirrors the given lines to the controller screen.
+    ///
+    /// Since the controller screen is slow to update, at most one changed line
+    /// is written per call. Call this every frame until the screen catches up.
+    pub fn mirror(&mut self, lines: &[String; 3]) {
+        if self.last_write.elapsed() < CONTROLLER_WRITE_INTERVAL {
+            return;
+        }
+        let Some(line) = (0..lines.len()).find(|&i| self.shown[i] != lines[i]) else {
+            return;
+        };
+
+        // Pad with spaces to overwrite the previous contents of the line
+        let text = format!(
+            "{:<width$.width$}",
+            lines[line],
+            width = CONTROLLER_LINE_WIDTH
+        );
+        self.last_write = Instant::now();
+        if self
+            .controller
+            .screen
+            .try_set_text(text, line as u8 + 1, 1)
+            .is_ok()
+        {
+            self.shown[line] = lines[line].clone();
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+
+use crate::builder::MAX_GRID_COLUMNS;
+
+/// Error returned when programmatically selecting a route fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectError {
+    /// The route index is past the end of the route list.
+    OutOfBounds { index: usize, len: usize },
+    /// No route matches the given name (and category).
+    NotFound,
+    /// Several routes in different categories share the given name.
+    ///
+    /// Use [`DoxaSelect::select_by_category_and_name`] to pick one.
+    ///
+    /// [`DoxaSelect::select_by_category_and_name`]: crate::DoxaSelect::select_by_category_and_name
+    Ambiguous,
+}
+
+impl fmt::Display for SelectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds { index, len } => {
+                write!(f, "route index {index} is out of bounds for {len} routes")
+            }
+            Self::NotFound => write!(f, "no route matches the given name"),
+            Self::Ambiguous => write!(f, "more than one route matches the given name"),
+        }
+    }
+}
+
+impl std::error::Error for SelectError {}
+
+/// Error returned when programmatically confirming the selected route fails.
+///
+/// These are the same reasons that disable the "Confirm" button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfirmError {
+    /// Calibration is required and hasn't succeeded.
+    NotCalibrated,
+    /// Required checklist items aren't done yet.
+    ChecklistIncomplete { remaining: usize },
+}
+
+impl fmt::Display for ConfirmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotCalibrated => write!(f, "calibration must succeed before confirming"),
+            Self::ChecklistIncomplete { remaining } => {
+                write!(f, "{remaining} required checklist items are not done")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfirmError {}
+
+/// Error returned by [`DoxaSelectBuilder::build`] when an option is invalid.
+///
+/// [`DoxaSelectBuilder::build`]: crate::DoxaSelectBuilder::build
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// No routes were given.
+    NoRoutes,
+    /// The default route index is past the end of the route list.
+    DefaultRouteOutOfBounds { index: usize, len: usize },
+    /// The frame rate is zero or more than the display's 60 FPS.
+    InvalidFps(u32),
+    /// The inactivity timeout is zero, so nothing would ever be rendered.
+    ZeroInactivityTimeout,
+    /// The card height is zero.
+    ZeroCardHeight,
+    /// The number of grid columns isn't between 1 and [`MAX_GRID_COLUMNS`].
+    InvalidGridColumns(usize),
+    /// The live diagnostics interval is zero.
+    ZeroDiagnosticsInterval,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoRoutes => write!(f, "DoxaSelect requires at least one route"),
+            Self::DefaultRouteOutOfBounds { index, len } => {
+                write!(f, "default route {index} is out of bounds for {len} routes")
+            }
+            Self::InvalidFps(fps) => write!(f, "frame rate {fps} is not between 1 and 60"),
+            Self::ZeroInactivityTimeout => write!(f, "inactivity timeout must not be zero"),
+            Self::ZeroCardHeight => write!(f, "card height must not be zero"),
+            Self::InvalidGridColumns(columns) => {
+                write!(
+                    f,
+                    "grid column count {columns} is not between 1 and {MAX_GRID_COLUMNS}"
+                )
+            }
+            Self::ZeroDiagnosticsInterval => {
+                write!(f, "live diagnostics interval must not be zero")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Error returned by [`Typography`] when font data can't be parsed.
+///
+/// [`Typography`]: crate::typography::Typography
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontError;
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "font data is not a valid TrueType or OpenType font")
+    }
+}
+
+impl std::error::Error for FontError {}
@@ -2,25 +2,100 @@
 #![feature(trait_alias)]
 #![feature(never_type)]
 
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 use autons::Selector;
-use vexide::{
-    display::Display,
-    task::{self, Task},
-};
+use vexide::{display::Display, task::Task};
 
+mod action;
+pub mod branding;
+mod builder;
+mod calibration;
+mod checklist;
+mod diagnostics;
 mod driver;
+mod error;
+mod persist;
+pub mod providers;
 mod route;
+pub mod theme;
+pub mod typography;
 mod view;
+mod watch;
 
+pub use action::*;
+pub use builder::{DoxaSelectBuilder, MAX_GRID_COLUMNS};
+pub use calibration::*;
+pub use checklist::*;
+pub use diagnostics::*;
+pub use error::*;
 pub use route::*;
+pub use view::{Framebuffer, Preview, PreviewScreen};
+pub use watch::*;
 
 /// External state shared between the selector's UI and logic.
 #[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd)]
 struct ExternalState {
     calibrating: bool,
+    /// Calibration progress in thousandths, if the interface reports it
+    calibration_progress: Option<u16>,
+    /// Line describing the current calibration step
+    calibration_status: Option<String>,
+    /// Outcome of the last calibration, or `None` if the robot hasn't been
+    /// calibrated yet
+    calibration: Option<CalibrationResult>,
     selection: usize,
+    /// Whether the selection has been confirmed by the user
+    confirmed: bool,
+    /// Whether the selection was restored from the SD card at startup
+    restored: bool,
+    /// (route_index) -> outcome of the last run of that route
+    runs: BTreeMap<usize, RouteRun>,
+    /// Why the selection can't be confirmed, as of the UI's last frame
+    confirm_blocker: Option<ConfirmError>,
+    /// Screen change requested through the [`DoxaSelect`] API, handled by the UI
+    request: Option<ScreenRequest>,
+}
+
+impl ExternalState {
+    /// Creates the state for an unconfirmed selection of the given route.
+    fn new(selection: usize) -> Self {
+        Self {
+            calibrating: false,
+            calibration_progress: None,
+            calibration_status: None,
+            calibration: None,
+            selection,
+            confirmed: false,
+            restored: false,
+            runs: BTreeMap::new(),
+            confirm_blocker: None,
+            request: None,
+        }
+    }
+}
+
+/// Screen change requested by code outside the UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
+enum ScreenRequest {
+    /// Show the selected route on the confirmation screen
+    ConfirmSelection,
+    /// Confirm the selected route
+    Confirm,
+}
+
+/// Outcome of a single autonomous run.
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd)]
+struct RouteRun {
+    /// Wall-clock time the route took
+    elapsed: Duration,
+    /// Error returned by the route, if it failed
+    error: Option<String>,
 }
 
 pub trait DoxaSelectInterface {
@@ -40,24 +115,99 @@ pub trait DoxaSelectInterface {
     fn calibrating_calibrating(&self) -> bool {
         panic!("when calibrating ui is enabled, you must implement calibrating_calibrating to return the calibration state");
     }
+    /// Returns how far calibration has got, from 0 to 1, if it's known.
+    ///
+    /// If this returns `Some`, a progress bar is shown while calibrating.
+    fn calibrating_progress(&self) -> Option<f32> {
+        None
+    }
+    /// Returns a short line describing the current calibration step, such as
+    /// "IMU 2/3" or "Zeroing lift".
+    fn calibrating_status(&self) -> Option<String> {
+        None
+    }
+    /// Returns the outcome of the calibration that just finished.
+    ///
+    /// This is called once `calibrating_calibrating` returns false after a
+    /// calibration. Failures and warnings are shown in an overlay with a
+    /// "Retry" button. Defaults to success.
+    fn calibrating_result(&self) -> CalibrationResult {
+        CalibrationResult::Success
+    }
+    /// Whether calibration can be cancelled.
+    ///
+    /// If true, a "Cancel" button will be shown while calibrating. You must
+    /// implement `calibrating_cancel` if this returns true.
+    fn calibrating_cancellable(&self) -> bool {
+        false
+    }
+    /// Stops the calibration process.
+    fn calibrating_cancel(&mut self) {
+        panic!("when calibration is cancellable, you must implement calibrating_cancel");
+    }
+
+    /// Returns the quick actions shown in the bottom bar.
+    ///
+    /// This is called every frame, so an action's label and whether it's
+    /// enabled can change at any time. Up to three buttons are shown in the
+    /// bar, counting "Calibrate" and "Diagnostics" when they're enabled; if
+    /// there are more, they're all moved to an "Actions" menu.
+    fn actions(&self) -> Vec<QuickAction> {
+        Vec::new()
+    }
+    /// Runs the action at the given index in the list returned by `actions`.
+    ///
+    /// This is only called for enabled actions.
+    fn on_action(&mut self, _index: usize) {}
+
+    /// Returns the pre-match checklist items that depend on the robot, such as
+    /// "IMU calibrated".
+    ///
+    /// These are shown after any items set with the builder's `checklist`. This
+    /// is called every frame, so use [`ChecklistItem::automatic`] for items the
+    /// robot can check itself.
+    fn checklist(&self) -> Vec<ChecklistItem> {
+        Vec::new()
+    }
 
     /// Whether the diagnostics screen is enabled.
     ///
     /// If true, a "Diagnostics" button will be shown in the UI. You must implement
-    /// the diagnostics_diagnostics method if this returns true.
+    /// either the diagnostics_entries or the diagnostics_diagnostics method if
+    /// this returns true.
     fn diagnostics_enable(&self) -> bool {
         false
     }
     /// Returns a list of key-value pairs representing diagnostics data.
     ///
-    /// There is a maximum of 16 entries.
+    /// Implement diagnostics_entries instead for sections, statuses, units and
+    /// gauges.
     fn diagnostics_diagnostics(&self) -> Vec<(String, String)> {
-        panic!("when diagnostics ui is enabled, you must implement diagnostics_diagnostics to return diagnostics");
+        panic!("when diagnostics ui is enabled, you must implement diagnostics_entries or diagnostics_diagnostics to return diagnostics");
+    }
+    /// Returns the entries shown on the diagnostics screen.
+    ///
+    /// There is no limit on the number of entries; the screen pages through
+    /// them. Defaults to the pairs from diagnostics_diagnostics as text entries.
+    fn diagnostics_entries(&self) -> Vec<DiagnosticEntry> {
+        self.diagnostics_diagnostics()
+            .into_iter()
+            .map(DiagnosticEntry::from)
+            .collect()
     }
     /// Whether the diagnostics screen should use a compact layout.
     fn diagnostics_compact(&self) -> bool {
         false
     }
+
+    /// Called when the selected route changes, before it is confirmed.
+    ///
+    /// This is also called at startup with the initial selection. Use it to
+    /// pre-position mechanisms or pre-load path data for the route.
+    fn on_selection_changed(&mut self, _route: RouteInfo) {}
+    /// Called when the selected route is confirmed, including when a selection
+    /// restored from the SD card at startup is confirmed again.
+    fn on_confirmed(&mut self, _route: RouteInfo) {}
 }
 
 /// Touchscreen-based autonomous route selector with animations and Material 3
@@ -66,64 +216,155 @@ pub trait DoxaSelectInterface {
 /// This struct implements the [`Selector`] trait and can be used with the `autons`
 /// [`SelectCompete`] trait if using vexide's competition runtime.
 ///
+/// The confirmed route is saved to the SD card and restored on the next boot,
+/// so a brownout or power cycle before the match doesn't reset the selection.
+/// The restored route is only confirmed again if calibration and the checklist
+/// allow it, just like confirming by hand.
+///
 /// [`SelectCompete`]: autons::compete::SelectCompete
 pub struct DoxaSelect<C: Category, R: 'static> {
     state: Rc<RefCell<ExternalState>>,
     routes: Vec<Route<C, R>>,
+    route_infos: Rc<[RouteInfo]>,
     _task: Task<()>,
 }
 
 impl<C: Category, R> DoxaSelect<C, R> {
     /// Creates a new selector from a [`Display`] peripheral and array of routes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `routes` is empty. Use [`DoxaSelect::builder`] to configure
+    /// the selector and handle errors instead.
     pub fn new(
         display: Display,
         routes: &[Route<C, R>],
         interface: impl DoxaSelectInterface + 'static,
     ) -> Self {
-        assert!(routes.len() > 0, "DoxaSelect requires at least one route.");
-
-        let categories = {
-            let mut cats = routes
-                .iter()
-                .map(|route| route.category)
-                .collect::<Vec<_>>();
-            cats.sort_unstable();
-            cats.dedup();
-            cats
-        };
-
-        let state = Rc::new(RefCell::new(ExternalState {
-            selection: 0,
-            calibrating: if interface.calibrating_enable() {
-                interface.calibrating_calibrating()
-            } else {
-                false
-            },
-        }));
-
-        let routes_vec = routes.to_vec();
-        Self {
-            state: state.clone(),
-            routes: routes.to_vec(),
-            _task: task::spawn(async move {
-                view::run(display, state, interface, routes_vec, categories).await;
-            }),
+        match Self::builder(display, routes).interface(interface).build() {
+            Ok(selector) => selector,
+            Err(error) => panic!("Failed to create DoxaSelect: {error}"),
         }
     }
 
+    /// Creates a builder for configuring a selector from a [`Display`]
+    /// peripheral and array of routes.
+    pub fn builder(display: Display, routes: &[Route<C, R>]) -> DoxaSelectBuilder<C, R> {
+        DoxaSelectBuilder::new(display, routes)
+    }
+
+    /// Returns a handle that can be used to wait for selection changes.
+    ///
+    /// This is useful for starting work that depends on the selected route,
+    /// such as loading path data, as soon as a route is picked.
+    pub fn watch(&self) -> SelectionWatch {
+        SelectionWatch::new(self.state.clone(), self.route_infos.clone())
+    }
+
+    /// Returns the currently selected route.
+    pub fn selected(&self) -> &Route<C, R> {
+        &self.routes[self.state.borrow().selection]
+    }
+
+    /// Returns whether the selected route has been confirmed.
+    pub fn is_confirmed(&self) -> bool {
+        self.state.borrow().confirmed
+    }
+
     /// Programmatically selects an autonomous route by index.
-    pub fn select(&mut self, index: usize) {
+    ///
+    /// The UI switches to the confirmation screen for the route.
+    pub fn select(&mut self, index: usize) -> Result<(), SelectError> {
+        if index >= self.routes.len() {
+            return Err(SelectError::OutOfBounds {
+                index,
+                len: self.routes.len(),
+            });
+        }
+
         let mut state = self.state.borrow_mut();
         state.selection = index;
+        state.confirmed = false;
+        state.restored = false;
+        state.request = Some(ScreenRequest::ConfirmSelection);
+        Ok(())
+    }
+
+    /// Programmatically selects the route with the given name.
+    ///
+    /// Fails with [`SelectError::Ambiguous`] if routes in several categories
+    /// share the name.
+    pub fn select_by_name(&mut self, name: &str) -> Result<(), SelectError> {
+        let mut matches = self
+            .routes
+            .iter()
+            .enumerate()
+            .filter(|(_, route)| route.name == name)
+            .map(|(index, _)| index);
+        let index = matches.next().ok_or(SelectError::NotFound)?;
+        if matches.next().is_some() {
+            return Err(SelectError::Ambiguous);
+        }
+        self.select(index)
+    }
+
+    /// Programmatically selects the route with the given category and name.
+    pub fn select_by_category_and_name(
+        &mut self,
+        category: C,
+        name: &str,
+    ) -> Result<(), SelectError> {
+        let index = self
+            .routes
+            .iter()
+            .position(|route| route.category == category && route.name == name)
+            .ok_or(SelectError::NotFound)?;
+        self.select(index)
+    }
+
+    /// Confirms the selected route, as if the user had tapped "Confirm".
+    ///
+    /// Like the button, this fails if calibration is required and hasn't
+    /// succeeded, or if required checklist items aren't done. The UI checks
+    /// again before confirming, so the route is never confirmed while blocked,
+    /// even if that changed since the last frame.
+    pub fn confirm(&mut self) -> Result<(), ConfirmError> {
+        let mut state = self.state.borrow_mut();
+        if let Some(blocker) = state.confirm_blocker {
+            return Err(blocker);
+        }
+        state.request = Some(ScreenRequest::Confirm);
+        Ok(())
     }
 }
 
 impl<C: Category, R> Selector<R> for DoxaSelect<C, R> {
     async fn run(&self, robot: &mut R) {
-        {
-            let state = self.state.borrow();
-            (self.routes[state.selection].callback)(robot)
+        let route = self.state.borrow().selection;
+        let start = Instant::now();
+        let result = self.routes[route].callback.call(robot).await;
+        let elapsed = start.elapsed();
+
+        let name = self.routes[route].name;
+        if let Err(error) = &result {
+            log::error!("Route {} failed after {:.1?}: {}", name, elapsed, error);
         }
-        .await;
+        if elapsed > self.routes[route].time_budget {
+            log::warn!(
+                "Route {} took {:.1?}, over its budget of {:.1?}",
+                name,
+                elapsed,
+                self.routes[route].time_budget
+            );
+        }
+
+        let mut state = self.state.borrow_mut();
+        state.runs.insert(
+            route,
+            RouteRun {
+                elapsed,
+                error: result.err(),
+            },
+        );
     }
 }
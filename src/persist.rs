@@ -0,0 +1,266 @@
+//! Persistence of the confirmed route and route shortcuts to the SD card.
+//!
+//! Routes are keyed by their category `Display` string and route name rather
+//! than their index, so reordering the route list between uploads doesn't
+//! restore the wrong route.
+
+use std::{fs, path::PathBuf};
+
+/// File on the SD card that the confirmed selection is saved to
+const SELECTION_FILE: &str = "doxa-selection.txt";
+
+/// First line of the selection file
+///
+/// Bump the version whenever the format changes so that files written by older
+/// versions of the crate are ignored instead of misinterpreted.
+const SELECTION_HEADER: &str = "doxa-selector selection v1";
+
+/// File on the SD card that favorite and recent routes are saved to
+const SHORTCUTS_FILE: &str = "doxa-shortcuts.txt";
+
+/// First line of the shortcuts file, versioned like [`SELECTION_HEADER`]
+const SHORTCUTS_HEADER: &str = "doxa-selector shortcuts v1";
+
+/// A route read back from the SD card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedRoute {
+    pub category: String,
+    pub name: String,
+}
+
+/// Favorite and recently confirmed routes read back from the SD card.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SavedShortcuts {
+    pub favorites: Vec<SavedRoute>,
+    /// Most recent first
+    pub recents: Vec<SavedRoute>,
+}
+
+/// Saves the confirmed route to the SD card.
+///
+/// Failures are logged and otherwise ignored, since a missing SD card shouldn't
+/// prevent the robot from running.
+pub fn save(category: &str, name: &str) {
+    if category.contains('\n') || name.contains('\n') {
+        log::warn!("Not saving selection: category or route name contains a newline");
+        return;
+    }
+    write(
+        SELECTION_FILE,
+        SELECTION_HEADER,
+        &[category.to_string(), name.to_string()],
+    );
+}
+
+/// Forgets the saved route, so that nothing is restored on the next boot.
+///
+/// The file is overwritten with an empty selection rather than deleted, since
+/// the SD card can't always delete files.
+pub fn clear() {
+    write(SELECTION_FILE, SELECTION_HEADER, &[]);
+}
+
+/// Loads the previously confirmed route from the SD card.
+///
+/// Returns `None` if there is no saved selection, or if the file was written by
+/// a different version of the crate or is corrupt.
+pub fn load() -> Option<SavedRoute> {
+    match read(SELECTION_FILE, SELECTION_HEADER)?.as_slice() {
+        // Cleared by `clear`
+        [] => None,
+        [category, name] => Some(SavedRoute {
+            category: category.clone(),
+            name: name.clone(),
+        }),
+        _ => {
+            log::warn!("Ignoring corrupt or outdated {}", SELECTION_FILE);
+            None
+        }
+    }
+}
+
+/// Saves the favorite and recent routes, given as `(category, name)` pairs, to
+/// the SD card.
+///
+/// Failures are logged and otherwise ignored, like [`save`].
+pub fn save_shortcuts(favorites: &[(&str, &str)], recents: &[(&str, &str)]) {
+    let invalid = |&(category, name): &(&str, &str)| {
+        category.contains(['\n', '\t']) || name.contains(['\n', '\t'])
+    };
+    if favorites.iter().chain(recents).any(invalid) {
+        log::warn!("Not saving shortcuts: a category or route name contains a newline or tab");
+        return;
+    }
+
+    let lines = favorites
+        .iter()
+        .map(|(category, name)| format!("favorite\t{category}\t{name}"))
+        .chain(
+            recents
+                .iter()
+                .map(|(category, name)| format!("recent\t{category}\t{name}")),
+        )
+        .collect::<Vec<_>>();
+    write(SHORTCUTS_FILE, SHORTCUTS_HEADER, &lines);
+}
+
+/// Loads the favorite and recent routes from the SD card.
+///
+/// Returns no shortcuts if there are none saved, or if the file was written by
+/// a different version of the crate or is corrupt.
+pub fn load_shortcuts() -> SavedShortcuts {
+    let Some(lines) = read(SHORTCUTS_FILE, SHORTCUTS_HEADER) else {
+        return SavedShortcuts::default();
+    };
+
+    let mut shortcuts = SavedShortcuts::default();
+    for line in lines {
+        let mut fields = line.split('\t');
+        let (Some(kind), Some(category), Some(name), None) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            log::warn!("Ignoring corrupt or outdated {}", SHORTCUTS_FILE);
+            return SavedShortcuts::default();
+        };
+        let route = SavedRoute {
+            category: category.to_string(),
+            name: name.to_string(),
+        };
+        match kind {
+            "favorite" => shortcuts.favorites.push(route),
+            "recent" => shortcuts.recents.push(route),
+            _ => {
+                log::warn!("Ignoring corrupt or outdated {}", SHORTCUTS_FILE);
+                return SavedShortcuts::default();
+            }
+        }
+    }
+    shortcuts
+}
+
+/// Returns where a file is kept.
+///
+/// Tests keep their files in the temporary directory rather than littering the
+/// working directory.
+fn path(file: &str) -> PathBuf {
+    if cfg!(test) {
+        std::env::temp_dir().join(file)
+    } else {
+        PathBuf::from(file)
+    }
+}
+
+/// Writes the header, the given lines and a checksum to a file.
+fn write(file: &str, header: &str, lines: &[String]) {
+    if let Err(e) = fs::write(path(file), contents(header, lines)) {
+        log::warn!("Failed to write {}: {}", file, e);
+    }
+}
+
+/// Reads the lines after the header of a file written by [`write`].
+///
+/// Returns `None` if the file doesn't exist, has a different header or fails
+/// its checksum.
+fn read(file: &str, header: &str) -> Option<Vec<String>> {
+    let raw = match fs::read_to_string(path(file)) {
+        Ok(raw) => raw,
+        Err(e) => {
+            log::info!("Nothing saved in {}: {}", file, e);
+            return None;
+        }
+    };
+
+    let parsed = parse(&raw, header);
+    if parsed.is_none() {
+        log::warn!("Ignoring corrupt or outdated {}", file);
+    }
+    parsed
+}
+
+/// Formats the contents of a file, the inverse of [`parse`].
+fn contents(header: &str, lines: &[String]) -> String {
+    let mut body = format!("{header}\n");
+    for line in lines {
+        body.push_str(line);
+        body.push('\n');
+    }
+    format!("{body}{:08x}\n", checksum(body.as_bytes()))
+}
+
+fn parse(raw: &str, header: &str) -> Option<Vec<String>> {
+    // The checksum is the last line and covers everything before it
+    let (body, checksum_line) = raw.strip_suffix('\n')?.rsplit_once('\n')?;
+    let body = format!("{body}\n");
+    if u32::from_str_radix(checksum_line, 16).ok()? != checksum(body.as_bytes()) {
+        return None;
+    }
+
+    let mut lines = body.lines();
+    if lines.next()? != header {
+        return None;
+    }
+    Some(lines.map(str::to_string).collect())
+}
+
+/// 32-bit FNV-1a hash, used to detect truncated or corrupt files
+fn checksum(data: &[u8]) -> u32 {
+    data.iter().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ *byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines() -> Vec<String> {
+        vec!["Red\tLeft AWP".to_string(), "Skills\tFull run".to_string()]
+    }
+
+    #[test]
+    fn round_trips() {
+        let raw = contents(SELECTION_HEADER, &lines());
+        assert_eq!(parse(&raw, SELECTION_HEADER), Some(lines()));
+        assert_eq!(
+            parse(&contents(SHORTCUTS_HEADER, &[]), SHORTCUTS_HEADER),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn rejects_bad_checksum() {
+        let raw = contents(SELECTION_HEADER, &lines());
+        // A corrupted body no longer matches the checksum
+        let corrupted = raw.replacen("Left", "Lift", 1);
+        assert_eq!(parse(&corrupted, SELECTION_HEADER), None);
+        // Neither does a corrupted or missing checksum
+        let (body, _) = raw.strip_suffix('\n').unwrap().rsplit_once('\n').unwrap();
+        assert_eq!(
+            parse(&format!("{body}\n00000000\n"), SELECTION_HEADER),
+            None
+        );
+        assert_eq!(parse(&format!("{body}\nnot hex\n"), SELECTION_HEADER), None);
+        assert_eq!(parse(&format!("{body}\n"), SELECTION_HEADER), None);
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        let raw = contents(SELECTION_HEADER, &lines());
+        for len in 0..raw.len() {
+            assert_eq!(parse(&raw[..len], SELECTION_HEADER), None, "{len} bytes");
+        }
+    }
+
+    #[test]
+    fn rejects_other_header() {
+        let raw = contents(SHORTCUTS_HEADER, &lines());
+        assert_eq!(parse(&raw, SELECTION_HEADER), None);
+    }
+
+    #[test]
+    fn checksum_is_fnv1a() {
+        assert_eq!(checksum(b""), 0x811c_9dc5);
+        assert_eq!(checksum(b"a"), 0xe40c_292c);
+        assert_eq!(checksum(b"foobar"), 0xbf9c_f968);
+    }
+}
@@ -0,0 +1,187 @@
+//! Ready-made diagnostics for common vexide devices.
+//!
+//! Each provider returns a group of entries under its own section, so an
+//! interface can compose the ones it wants in
+//! [`DoxaSelectInterface::diagnostics_entries`]:
+//!
+//! ```ignore
+//! fn diagnostics_entries(&self) -> Vec<DiagnosticEntry> {
+//!     let mut entries = providers::battery();
+//!     entries.extend(providers::motors(&[
+//!         ("Left drive", &self.left_drive),
+//!         ("Right drive", &self.right_drive),
+//!     ]));
+//!     entries.extend(providers::inertial("IMU", &self.imu));
+//!     entries.extend(providers::competition());
+//!     entries
+//! }
+//! ```
+//!
+//! [`DoxaSelectInterface::diagnostics_entries`]: crate::DoxaSelectInterface::diagnostics_entries
+
+use vex_sdk::{V5_DeviceType, V5_MAX_DEVICE_PORTS};
+use vexide::{
+    competition::{self, CompetitionMode, CompetitionSystem},
+    smart::{imu::InertialSensor, motor::Motor, SmartDeviceType},
+};
+
+use crate::{DiagnosticEntry, DiagnosticStatus};
+
+/// Number of smart ports on a V5 brain
+const SMART_PORTS: usize = 21;
+
+/// Battery charge below which its row shows a warning, in percent
+const BATTERY_WARNING: f64 = 30.0;
+/// Battery charge below which its row shows an error, in percent
+const BATTERY_ERROR: f64 = 15.0;
+
+/// Motor temperature above which its row shows a warning, in °C
+const MOTOR_WARNING_TEMPERATURE: f64 = 45.0;
+/// Motor temperature at which V5 motors start limiting their power, in °C
+const MOTOR_LIMIT_TEMPERATURE: f64 = 55.0;
+/// Largest current a V5 motor draws, in amps
+const MOTOR_MAX_CURRENT: f64 = 2.5;
+
+/// Battery charge, voltage and current.
+pub fn battery() -> Vec<DiagnosticEntry> {
+    let charge = vexide::battery::capacity() * 100.0;
+    vec![
+        DiagnosticEntry::number("Charge", charge)
+            .section("Battery")
+            .unit("%")
+            .precision(0)
+            .range(0.0, 100.0)
+            .status(if charge < BATTERY_ERROR {
+                DiagnosticStatus::Error
+            } else if charge < BATTERY_WARNING {
+                DiagnosticStatus::Warning
+            } else {
+                DiagnosticStatus::Ok
+            }),
+        DiagnosticEntry::number("Voltage", vexide::battery::voltage())
+            .section("Battery")
+            .unit("V")
+            .precision(1),
+        DiagnosticEntry::number("Current", vexide::battery::current())
+            .section("Battery")
+            .unit("A")
+            .precision(1),
+    ]
+}
+
+/// Type of device plugged into each of the 21 smart ports.
+///
+/// Handy for spotting an unplugged cable before a match. Empty ports are
+/// listed too so that every port has a row. The types are read by port number,
+/// so ports already owned by a `Motor` or other device are listed as well.
+pub fn smart_ports() -> Vec<DiagnosticEntry> {
+    let mut devices = [V5_DeviceType::kDeviceTypeNoSensor; V5_MAX_DEVICE_PORTS];
+    // SAFETY: The buffer has room for the status of every device port, which
+    // is only read, so it doesn't interfere with the devices that own them.
+    unsafe {
+        vex_sdk::vexDeviceGetStatus(devices.as_mut_ptr());
+    }
+    devices[..SMART_PORTS]
+        .iter()
+        .enumerate()
+        .map(|(index, &device)| {
+            DiagnosticEntry::text(
+                format!("Port {}", index + 1),
+                if device == V5_DeviceType::kDeviceTypeNoSensor {
+                    "Empty".to_string()
+                } else {
+                    format!("{:?}", SmartDeviceType::from(device))
+                },
+            )
+            .section("Smart ports")
+        })
+        .collect()
+}
+
+/// Temperature and current of each named motor.
+///
+/// Motors that aren't connected are shown as errors.
+pub fn motors(motors: &[(&str, &Motor)]) -> Vec<DiagnosticEntry> {
+    let mut entries = Vec::with_capacity(motors.len() * 2);
+    for (name, motor) in motors {
+        let (Ok(temperature), Ok(current)) = (motor.temperature(), motor.current()) else {
+            entries.push(
+                DiagnosticEntry::text(*name, "Disconnected")
+                    .section("Motors")
+                    .status(DiagnosticStatus::Error),
+            );
+            continue;
+        };
+        entries.push(
+            DiagnosticEntry::number(format!("{name} temp"), temperature)
+                .section("Motors")
+                .unit("°C")
+                .precision(1)
+                .range(20.0, MOTOR_LIMIT_TEMPERATURE)
+                .status(if temperature >= MOTOR_LIMIT_TEMPERATURE {
+                    DiagnosticStatus::Error
+                } else if temperature >= MOTOR_WARNING_TEMPERATURE {
+                    DiagnosticStatus::Warning
+                } else {
+                    DiagnosticStatus::Ok
+                }),
+        );
+        entries.push(
+            DiagnosticEntry::number(format!("{name} current"), current)
+                .section("Motors")
+                .unit("A")
+                .range(0.0, MOTOR_MAX_CURRENT),
+        );
+    }
+    entries
+}
+
+/// Heading and calibration status of an inertial sensor.
+pub fn inertial(name: &str, imu: &InertialSensor) -> Vec<DiagnosticEntry> {
+    match imu.is_calibrating() {
+        Err(_) => vec![DiagnosticEntry::text(name, "Disconnected")
+            .section("Inertial")
+            .status(DiagnosticStatus::Error)],
+        Ok(true) => vec![DiagnosticEntry::text(name, "Calibrating")
+            .section("Inertial")
+            .status(DiagnosticStatus::Warning)],
+        Ok(false) => match imu.heading() {
+            Ok(heading) => {
+                vec![
+                    DiagnosticEntry::number(format!("{name} heading"), heading.as_degrees())
+                        .section("Inertial")
+                        .unit("°")
+                        .precision(1),
+                ]
+            }
+            // A heading that can't be read mustn't look like a valid 0°
+            Err(_) => vec![DiagnosticEntry::text(format!("{name} heading"), "—")
+                .section("Inertial")
+                .status(DiagnosticStatus::Error)],
+        },
+    }
+}
+
+/// Competition mode and how the robot is connected to field control.
+pub fn competition() -> Vec<DiagnosticEntry> {
+    let mode = match competition::mode() {
+        CompetitionMode::Disabled => "Disabled",
+        CompetitionMode::Autonomous => "Autonomous",
+        CompetitionMode::Driver => "Driver",
+    };
+    let connection = match competition::system() {
+        Some(CompetitionSystem::FieldControl) => "Field control",
+        Some(CompetitionSystem::CompetitionSwitch) => "Competition switch",
+        None => "Not connected",
+    };
+    vec![
+        DiagnosticEntry::text("Mode", mode).section("Competition"),
+        DiagnosticEntry::text("Connection", connection)
+            .section("Competition")
+            .status(if competition::is_connected() {
+                DiagnosticStatus::Ok
+            } else {
+                DiagnosticStatus::Warning
+            }),
+    ]
+}
@@ -1,16 +1,87 @@
 use std::{
-    fmt::{Debug, Display},
+    fmt::{self, Debug, Display},
     future::Future,
     pin::Pin,
+    rc::Rc,
+    time::Duration,
 };
 
 pub trait Category = Clone + Copy + Eq + Debug + Display + Ord + 'static;
-type RouteFn<Shared> = for<'s> fn(&'s mut Shared) -> Pin<Box<dyn Future<Output = ()> + 's>>;
+type RouteFn<Shared> =
+    for<'s> fn(&'s mut Shared) -> Pin<Box<dyn Future<Output = RouteResult> + 's>>;
+type RouteClosure<Shared> =
+    dyn for<'s> Fn(&'s mut Shared) -> Pin<Box<dyn Future<Output = RouteResult> + 's>>;
+
+/// Time budget of a match autonomous period
+pub const MATCH_TIME_BUDGET: Duration = Duration::from_secs(15);
+
+/// Time budget of an autonomous skills run
+pub const SKILLS_TIME_BUDGET: Duration = Duration::from_secs(60);
+
+/// Outcome of running a route, with the error message if it failed.
+pub type RouteResult = Result<(), String>;
+
+/// Conversion from a route function's return value to a [`RouteResult`].
+///
+/// Implemented for `()`, for routes that can't fail, and for `Result<(), E>`
+/// where `E` implements [`Display`].
+pub trait IntoRouteResult {
+    fn into_route_result(self) -> RouteResult;
+}
+
+impl IntoRouteResult for () {
+    fn into_route_result(self) -> RouteResult {
+        Ok(())
+    }
+}
+
+impl<E: Display> IntoRouteResult for Result<(), E> {
+    fn into_route_result(self) -> RouteResult {
+        self.map_err(|error| error.to_string())
+    }
+}
+
+/// The function that runs a [`Route`].
+pub enum RouteCallback<R> {
+    /// A plain function pointer, as created by most forms of [`route!()`].
+    Fn(RouteFn<R>),
+    /// A closure, which can capture configuration such as offsets or goal
+    /// counts so that one route body can be registered several times.
+    Closure(Rc<RouteClosure<R>>),
+}
+
+impl<R> RouteCallback<R> {
+    /// Starts the route, returning a future that completes when it's done.
+    pub fn call<'s>(&self, robot: &'s mut R) -> Pin<Box<dyn Future<Output = RouteResult> + 's>> {
+        match self {
+            Self::Fn(callback) => callback(robot),
+            Self::Closure(callback) => callback(robot),
+        }
+    }
+}
+
+impl<R> Clone for RouteCallback<R> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Fn(callback) => Self::Fn(*callback),
+            Self::Closure(callback) => Self::Closure(callback.clone()),
+        }
+    }
+}
+
+impl<R> Debug for RouteCallback<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fn(callback) => f.debug_tuple("Fn").field(callback).finish(),
+            Self::Closure(_) => f.debug_tuple("Closure").finish_non_exhaustive(),
+        }
+    }
+}
 
 /// Route entry for [`DoxaSelect`].
 ///
 /// These are provided to [`DoxaSelect`] in the form of an array passed to [`DoxaSelect`].
-/// Route entries contain a function pointer to the provided route function, as well as a human-readable
+/// Route entries contain a function pointer or closure that runs the route, as well as a human-readable
 /// name for the route that is displayed in the selector's UI.
 ///
 /// It's recommended to use the [`route!()`] macro to aid in creating instances of this struct.
@@ -21,7 +92,182 @@ pub struct Route<C: Category, R> {
     pub category: C,
     pub name: &'static str,
     pub description: &'static str,
-    pub callback: RouteFn<R>,
+    pub callback: RouteCallback<R>,
+    /// Routes that take longer than this to run are flagged in the UI.
+    pub time_budget: Duration,
+    /// Planned path, drawn on the confirmation screen so the drive team can
+    /// check that the robot is set up on the right side.
+    pub path: Option<RoutePath>,
+    /// Alliance the route is for, shown once it's confirmed
+    pub alliance: Option<Alliance>,
+    /// Side of the field the robot starts on, shown once it's confirmed
+    pub side: Option<FieldSide>,
+}
+
+impl<C: Category, R> Route<C, R> {
+    /// Creates a route that runs a closure.
+    ///
+    /// Unlike function pointers, closures can capture configuration, so the
+    /// same route body can be registered under several parameter sets. The
+    /// returned future must resolve to a [`RouteResult`]; see
+    /// [`IntoRouteResult`] for converting the route's own return value.
+    pub fn from_closure<F>(
+        category: C,
+        name: &'static str,
+        description: &'static str,
+        callback: F,
+    ) -> Self
+    where
+        F: for<'s> Fn(&'s mut R) -> Pin<Box<dyn Future<Output = RouteResult> + 's>> + 'static,
+    {
+        Self {
+            category,
+            name,
+            description,
+            callback: RouteCallback::Closure(Rc::new(callback)),
+            time_budget: MATCH_TIME_BUDGET,
+            path: None,
+            alliance: None,
+            side: None,
+        }
+    }
+
+    /// Sets how long the route is expected to take at most.
+    ///
+    /// Defaults to [`MATCH_TIME_BUDGET`]. Use [`SKILLS_TIME_BUDGET`] for skills
+    /// routes.
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = time_budget;
+        self
+    }
+
+    /// Sets the planned path of the route, which is drawn on the confirmation
+    /// screen.
+    ///
+    /// ```ignore
+    /// route!(Category::Left, Robot::left).with_path(
+    ///     Pose::new(-60.0, -36.0, 90.0),
+    ///     [Pose::new(-24.0, -36.0, 90.0), Pose::new(-24.0, 0.0, 0.0)],
+    /// )
+    /// ```
+    pub fn with_path(mut self, start: Pose, waypoints: impl IntoIterator<Item = Pose>) -> Self {
+        self.path = Some(RoutePath {
+            start,
+            waypoints: waypoints.into_iter().collect(),
+        });
+        self
+    }
+
+    /// Sets the alliance the route is for.
+    pub fn with_alliance(mut self, alliance: Alliance) -> Self {
+        self.alliance = Some(alliance);
+        self
+    }
+
+    /// Sets the side of the field the robot starts on, as seen by the drive
+    /// team.
+    pub fn with_side(mut self, side: FieldSide) -> Self {
+        self.side = Some(side);
+        self
+    }
+}
+
+/// Alliance of a [`Route`], set with [`Route::with_alliance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alliance {
+    Red,
+    Blue,
+}
+
+impl Display for Alliance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Red => write!(f, "Red alliance"),
+            Self::Blue => write!(f, "Blue alliance"),
+        }
+    }
+}
+
+/// Starting side of a [`Route`], set with [`Route::with_side`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldSide {
+    Left,
+    Right,
+}
+
+impl Display for FieldSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Left => write!(f, "Left side"),
+            Self::Right => write!(f, "Right side"),
+        }
+    }
+}
+
+/// Position and heading of the robot on the field.
+///
+/// Positions are in inches from the center of the field, with +x to the right
+/// and +y away from the drive team, as seen from behind the field image.
+/// Headings are in degrees clockwise from +y.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pose {
+    pub x: f32,
+    pub y: f32,
+    pub heading: f32,
+}
+
+impl Pose {
+    pub const fn new(x: f32, y: f32, heading: f32) -> Self {
+        Self { x, y, heading }
+    }
+}
+
+/// Planned path of a [`Route`], set with [`Route::with_path`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutePath {
+    /// Where the robot is set up before the match
+    pub start: Pose,
+    /// Poses the robot passes through, in order
+    pub waypoints: Vec<Pose>,
+}
+
+/// Returns the distinct categories of the given routes, in sorted order.
+pub(crate) fn categories<C: Category, R>(routes: &[Route<C, R>]) -> Vec<C> {
+    let mut categories = routes
+        .iter()
+        .map(|route| route.category)
+        .collect::<Vec<_>>();
+    categories.sort_unstable();
+    categories.dedup();
+    categories
+}
+
+/// Description of a route, passed to [`DoxaSelectInterface`] hooks and
+/// returned by [`SelectionWatch`].
+///
+/// [`DoxaSelectInterface`]: crate::DoxaSelectInterface
+/// [`SelectionWatch`]: crate::SelectionWatch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteInfo {
+    /// Index of the route in the list passed to [`DoxaSelect`]
+    ///
+    /// [`DoxaSelect`]: crate::DoxaSelect
+    pub index: usize,
+    /// The route's category, formatted using its [`Display`] implementation
+    pub category: String,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+impl RouteInfo {
+    pub(crate) fn new<C: Category, R>(index: usize, route: &Route<C, R>) -> Self {
+        Self {
+            index,
+            category: route.category.to_string(),
+            name: route.name,
+            description: route.description,
+        }
+    }
 }
 
 impl<C: Category, R> Clone for Route<C, R> {
@@ -30,7 +276,11 @@ impl<C: Category, R> Clone for Route<C, R> {
             category: self.category,
             name: self.name,
             description: self.description,
-            callback: self.callback,
+            callback: self.callback.clone(),
+            time_budget: self.time_budget,
+            path: self.path.clone(),
+            alliance: self.alliance,
+            side: self.side,
         }
     }
 }
@@ -45,6 +295,28 @@ impl<C: Category, R> Clone for Route<C, R> {
 ///     route!("Route 2", "Another test route.", Robot::route_2),
 /// ];
 /// ```
+///
+/// Route functions may return either `()` or `Result<(), E>` where `E`
+/// implements [`Display`]. Errors are shown on the selector's screen once
+/// autonomous ends.
+///
+/// Routes default to [`MATCH_TIME_BUDGET`]; skills routes should be given a
+/// longer budget with [`Route::with_time_budget`]:
+///
+/// ```ignore
+/// route!(Category::Skills, Robot::skills).with_time_budget(SKILLS_TIME_BUDGET)
+/// ```
+///
+/// Passing an extra argument expression after the route function creates a
+/// closure-based route which passes a clone of the argument to the route
+/// method:
+///
+/// ```ignore
+/// let routes = [
+///     route!(Category::Left, "Left (2 goals)", "", Robot::left, 2),
+///     route!(Category::Left, "Left (3 goals)", "", Robot::left, 3),
+/// ];
+/// ```
 #[macro_export]
 macro_rules! route {
     ($category:expr, $func:path) => {{
@@ -52,7 +324,15 @@ macro_rules! route {
             category: $category,
             name: stringify!($func),
             description: "",
-            callback: |robot| ::std::boxed::Box::pin($func(robot)),
+            callback: ::doxa_selector::RouteCallback::Fn(|robot| {
+                ::std::boxed::Box::pin(async move {
+                    ::doxa_selector::IntoRouteResult::into_route_result($func(robot).await)
+                })
+            }),
+            time_budget: ::doxa_selector::MATCH_TIME_BUDGET,
+            path: ::std::option::Option::None,
+            alliance: ::std::option::Option::None,
+            side: ::std::option::Option::None,
         }
     }};
     ($category:expr, $func:path, $description:expr) => {{
@@ -60,7 +340,15 @@ macro_rules! route {
             category: $category,
             name: stringify!($func),
             description: $description,
-            callback: |robot| ::std::boxed::Box::pin($func(robot)),
+            callback: ::doxa_selector::RouteCallback::Fn(|robot| {
+                ::std::boxed::Box::pin(async move {
+                    ::doxa_selector::IntoRouteResult::into_route_result($func(robot).await)
+                })
+            }),
+            time_budget: ::doxa_selector::MATCH_TIME_BUDGET,
+            path: ::std::option::Option::None,
+            alliance: ::std::option::Option::None,
+            side: ::std::option::Option::None,
         }
     }};
     ($category:expr, $name:expr, $func:path) => {{
@@ -68,7 +356,15 @@ macro_rules! route {
             category: $category,
             name: $name,
             description: "",
-            callback: |robot| ::std::boxed::Box::pin($func(robot)),
+            callback: ::doxa_selector::RouteCallback::Fn(|robot| {
+                ::std::boxed::Box::pin(async move {
+                    ::doxa_selector::IntoRouteResult::into_route_result($func(robot).await)
+                })
+            }),
+            time_budget: ::doxa_selector::MATCH_TIME_BUDGET,
+            path: ::std::option::Option::None,
+            alliance: ::std::option::Option::None,
+            side: ::std::option::Option::None,
         }
     }};
     ($category:expr, $name:expr, $description:expr, $func:path) => {{
@@ -76,9 +372,26 @@ macro_rules! route {
             category: $category,
             name: $name,
             description: $description,
-            callback: |robot| ::std::boxed::Box::pin($func(robot)),
+            callback: ::doxa_selector::RouteCallback::Fn(|robot| {
+                ::std::boxed::Box::pin(async move {
+                    ::doxa_selector::IntoRouteResult::into_route_result($func(robot).await)
+                })
+            }),
+            time_budget: ::doxa_selector::MATCH_TIME_BUDGET,
+            path: ::std::option::Option::None,
+            alliance: ::std::option::Option::None,
+            side: ::std::option::Option::None,
         }
     }};
+    ($category:expr, $name:expr, $description:expr, $func:path, $args:expr) => {{
+        let args = $args;
+        ::doxa_selector::Route::from_closure($category, $name, $description, move |robot| {
+            let args = ::std::clone::Clone::clone(&args);
+            ::std::boxed::Box::pin(async move {
+                ::doxa_selector::IntoRouteResult::into_route_result($func(robot, args).await)
+            })
+        })
+    }};
 }
 #[allow(unused)]
 pub use route;
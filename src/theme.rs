@@ -0,0 +1,497 @@
+//! Runtime color theming based on Material 3 color roles.
+
+use embedded_graphics::pixelcolor::{Rgb888, RgbColor};
+
+/// Seed color of the default DOXA Robotics theme
+const DOXA_SEED: Rgb888 = Rgb888::new(0x4B, 0x5C, 0x92);
+
+/// Whether the light or dark color scheme of a [`Theme`] is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Brightness {
+    Light,
+    #[default]
+    Dark,
+}
+
+/// Colors used by the selector's UI, with a light and a dark scheme.
+///
+/// The default theme is DOXA Robotics' dark blue theme. Use
+/// [`Theme::from_seed`] to generate a theme from a single brand color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub light: ColorScheme,
+    pub dark: ColorScheme,
+    /// Which of the two schemes is shown
+    pub brightness: Brightness,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            light: ColorScheme::light(&CorePalette::from_seed(DOXA_SEED)),
+            dark: ColorScheme::DOXA_DARK,
+            brightness: Brightness::Dark,
+        }
+    }
+}
+
+impl Theme {
+    /// Generates a theme with light and dark schemes from a single seed color.
+    ///
+    /// The dark scheme is shown by default.
+    pub fn from_seed(seed: Rgb888) -> Self {
+        let palette = CorePalette::from_seed(seed);
+        Self {
+            light: ColorScheme::light(&palette),
+            dark: ColorScheme::dark(&palette),
+            brightness: Brightness::Dark,
+        }
+    }
+
+    /// Sets which of the two schemes is shown.
+    pub fn with_brightness(mut self, brightness: Brightness) -> Self {
+        self.brightness = brightness;
+        self
+    }
+
+    /// Returns the scheme that is currently shown.
+    pub fn scheme(&self) -> &ColorScheme {
+        match self.brightness {
+            Brightness::Light => &self.light,
+            Brightness::Dark => &self.dark,
+        }
+    }
+}
+
+/// Material 3 color roles.
+///
+/// See the [Material 3 documentation](https://m3.material.io/styles/color/roles)
+/// for what each role is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorScheme {
+    pub primary: Rgb888,
+    pub on_primary: Rgb888,
+    pub primary_container: Rgb888,
+    pub on_primary_container: Rgb888,
+    pub secondary: Rgb888,
+    pub on_secondary: Rgb888,
+    pub secondary_container: Rgb888,
+    pub on_secondary_container: Rgb888,
+    pub tertiary: Rgb888,
+    pub on_tertiary: Rgb888,
+    pub tertiary_container: Rgb888,
+    pub on_tertiary_container: Rgb888,
+    pub error: Rgb888,
+    pub on_error: Rgb888,
+    pub error_container: Rgb888,
+    pub on_error_container: Rgb888,
+    pub background: Rgb888,
+    pub on_background: Rgb888,
+    pub surface: Rgb888,
+    pub on_surface: Rgb888,
+    pub surface_variant: Rgb888,
+    pub on_surface_variant: Rgb888,
+    pub outline: Rgb888,
+    pub outline_variant: Rgb888,
+    pub inverse_surface: Rgb888,
+    pub inverse_on_surface: Rgb888,
+    pub inverse_primary: Rgb888,
+    pub surface_container_lowest: Rgb888,
+    pub surface_container_low: Rgb888,
+    pub surface_container: Rgb888,
+    pub surface_container_high: Rgb888,
+    pub surface_container_highest: Rgb888,
+}
+
+impl ColorScheme {
+    /// DOXA Robotics' hand-tuned dark scheme
+    pub const DOXA_DARK: Self = Self {
+        primary: Rgb888::new(0xB4, 0xC5, 0xFF),
+        on_primary: Rgb888::new(0x1B, 0x2D, 0x60),
+        primary_container: Rgb888::new(0x33, 0x44, 0x78),
+        on_primary_container: Rgb888::new(0xDB, 0xE1, 0xFF),
+        secondary: Rgb888::new(0xC1, 0xC5, 0xDD),
+        on_secondary: Rgb888::new(0x2B, 0x30, 0x42),
+        secondary_container: Rgb888::new(0x41, 0x46, 0x59),
+        on_secondary_container: Rgb888::new(0xDD, 0xE1, 0xF9),
+        tertiary: Rgb888::new(0xE2, 0xBB, 0xDB),
+        on_tertiary: Rgb888::new(0x42, 0x27, 0x40),
+        tertiary_container: Rgb888::new(0x5B, 0x3D, 0x58),
+        on_tertiary_container: Rgb888::new(0xFF, 0xD6, 0xF7),
+        error: Rgb888::new(0xFF, 0xB4, 0xAB),
+        on_error: Rgb888::new(0x69, 0x00, 0x05),
+        error_container: Rgb888::new(0x93, 0x00, 0x0A),
+        on_error_container: Rgb888::new(0xFF, 0xDA, 0xD6),
+        background: Rgb888::new(0x12, 0x13, 0x18),
+        on_background: Rgb888::new(0xE3, 0xE2, 0xE9),
+        surface: Rgb888::new(0x12, 0x13, 0x18),
+        on_surface: Rgb888::new(0xE3, 0xE2, 0xE9),
+        surface_variant: Rgb888::new(0x45, 0x46, 0x4F),
+        on_surface_variant: Rgb888::new(0xC5, 0xC6, 0xD0),
+        outline: Rgb888::new(0x8F, 0x90, 0x9A),
+        outline_variant: Rgb888::new(0x45, 0x46, 0x4F),
+        inverse_surface: Rgb888::new(0xE3, 0xE2, 0xE9),
+        inverse_on_surface: Rgb888::new(0x2F, 0x30, 0x36),
+        inverse_primary: Rgb888::new(0x4B, 0x5C, 0x92),
+        surface_container_lowest: Rgb888::new(0x0D, 0x0E, 0x13),
+        surface_container_low: Rgb888::new(0x1A, 0x1B, 0x21),
+        surface_container: Rgb888::new(0x1E, 0x1F, 0x25),
+        surface_container_high: Rgb888::new(0x29, 0x2A, 0x2F),
+        surface_container_highest: Rgb888::new(0x34, 0x34, 0x3A),
+    };
+
+    /// Builds a light scheme from a tonal palette, using the Material 3
+    /// baseline tone mapping.
+    pub fn light(palette: &CorePalette) -> Self {
+        let CorePalette {
+            primary: p,
+            secondary: s,
+            tertiary: t,
+            neutral: n,
+            neutral_variant: nv,
+            error: e,
+        } = palette;
+        Self {
+            primary: p.tone(40),
+            on_primary: p.tone(100),
+            primary_container: p.tone(90),
+            on_primary_container: p.tone(10),
+            secondary: s.tone(40),
+            on_secondary: s.tone(100),
+            secondary_container: s.tone(90),
+            on_secondary_container: s.tone(10),
+            tertiary: t.tone(40),
+            on_tertiary: t.tone(100),
+            tertiary_container: t.tone(90),
+            on_tertiary_container: t.tone(10),
+            error: e.tone(40),
+            on_error: e.tone(100),
+            error_container: e.tone(90),
+            on_error_container: e.tone(10),
+            background: n.tone(98),
+            on_background: n.tone(10),
+            surface: n.tone(98),
+            on_surface: n.tone(10),
+            surface_variant: nv.tone(90),
+            on_surface_variant: nv.tone(30),
+            outline: nv.tone(50),
+            outline_variant: nv.tone(80),
+            inverse_surface: n.tone(20),
+            inverse_on_surface: n.tone(95),
+            inverse_primary: p.tone(80),
+            surface_container_lowest: n.tone(100),
+            surface_container_low: n.tone(96),
+            surface_container: n.tone(94),
+            surface_container_high: n.tone(92),
+            surface_container_highest: n.tone(90),
+        }
+    }
+
+    /// Builds a dark scheme from a tonal palette, using the Material 3
+    /// baseline tone mapping.
+    pub fn dark(palette: &CorePalette) -> Self {
+        let CorePalette {
+            primary: p,
+            secondary: s,
+            tertiary: t,
+            neutral: n,
+            neutral_variant: nv,
+            error: e,
+        } = palette;
+        Self {
+            primary: p.tone(80),
+            on_primary: p.tone(20),
+            primary_container: p.tone(30),
+            on_primary_container: p.tone(90),
+            secondary: s.tone(80),
+            on_secondary: s.tone(20),
+            secondary_container: s.tone(30),
+            on_secondary_container: s.tone(90),
+            tertiary: t.tone(80),
+            on_tertiary: t.tone(20),
+            tertiary_container: t.tone(30),
+            on_tertiary_container: t.tone(90),
+            error: e.tone(80),
+            on_error: e.tone(20),
+            error_container: e.tone(30),
+            on_error_container: e.tone(90),
+            background: n.tone(6),
+            on_background: n.tone(90),
+            surface: n.tone(6),
+            on_surface: n.tone(90),
+            surface_variant: nv.tone(30),
+            on_surface_variant: nv.tone(80),
+            outline: nv.tone(60),
+            outline_variant: nv.tone(30),
+            inverse_surface: n.tone(90),
+            inverse_on_surface: n.tone(20),
+            inverse_primary: p.tone(40),
+            surface_container_lowest: n.tone(4),
+            surface_container_low: n.tone(10),
+            surface_container: n.tone(12),
+            surface_container_high: n.tone(17),
+            surface_container_highest: n.tone(22),
+        }
+    }
+}
+
+/// The six tonal palettes a Material 3 color scheme is built from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CorePalette {
+    pub primary: TonalPalette,
+    pub secondary: TonalPalette,
+    pub tertiary: TonalPalette,
+    pub neutral: TonalPalette,
+    pub neutral_variant: TonalPalette,
+    pub error: TonalPalette,
+}
+
+impl CorePalette {
+    /// Derives all palettes from a single seed color.
+    ///
+    /// Like Material 3, the secondary and neutral palettes share the seed's hue
+    /// with less saturation, and the tertiary palette is rotated by 60°.
+    pub fn from_seed(seed: Rgb888) -> Self {
+        let (hue, saturation) = hue_saturation(seed);
+        Self {
+            primary: TonalPalette::new(hue, saturation.max(0.48)),
+            secondary: TonalPalette::new(hue, saturation / 3.0),
+            tertiary: TonalPalette::new(hue + 60.0, saturation / 2.0),
+            neutral: TonalPalette::new(hue, saturation / 12.0),
+            neutral_variant: TonalPalette::new(hue, saturation / 6.0),
+            error: TonalPalette::new(5.0, 0.84),
+        }
+    }
+}
+
+/// A color's hue and saturation at every tone from black (0) to white (100).
+///
+/// Tones are approximated with HSL lightness rather than Material 3's
+/// perceptual HCT color space, which keeps the generator small enough to run
+/// on the brain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TonalPalette {
+    /// Hue in degrees
+    hue: f32,
+    /// Saturation from 0 to 1
+    saturation: f32,
+}
+
+impl TonalPalette {
+    /// Creates a palette from a hue in degrees and a saturation from 0 to 1.
+    pub fn new(hue: f32, saturation: f32) -> Self {
+        Self {
+            hue: hue.rem_euclid(360.0),
+            saturation: saturation.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Returns the color at the given tone, from 0 (black) to 100 (white).
+    pub fn tone(&self, tone: u8) -> Rgb888 {
+        let lightness = f32::from(tone.min(100)) / 100.0;
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * self.saturation;
+        let h = self.hue / 60.0;
+        let x = chroma * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = lightness - chroma / 2.0;
+        let channel = |value: f32| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+        Rgb888::new(channel(r), channel(g), channel(b))
+    }
+}
+
+/// Returns the HSL hue in degrees and saturation from 0 to 1 of a color.
+fn hue_saturation(color: Rgb888) -> (f32, f32) {
+    let r = f32::from(color.r()) / 255.0;
+    let g = f32::from(color.g()) / 255.0;
+    let b = f32::from(color.b()) / 255.0;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    if delta == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let lightness = (max + min) / 2.0;
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    (hue, saturation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Seeds whose generated schemes are checked, from the default to plain
+    /// and muted colors
+    const SEEDS: [Rgb888; 4] = [
+        DOXA_SEED,
+        Rgb888::new(0, 0, 255),
+        Rgb888::new(255, 0, 255),
+        Rgb888::new(128, 128, 128),
+    ];
+
+    /// WCAG relative luminance
+    fn luminance(color: Rgb888) -> f32 {
+        let linear = |channel: u8| {
+            let value = f32::from(channel) / 255.0;
+            if value <= 0.039_28 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(color.r()) + 0.7152 * linear(color.g()) + 0.0722 * linear(color.b())
+    }
+
+    /// WCAG contrast ratio, from 1 to 21
+    fn contrast(a: Rgb888, b: Rgb888) -> f32 {
+        let (a, b) = (luminance(a), luminance(b));
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Distance between two hues in degrees, going the short way around
+    fn hue_distance(a: f32, b: f32) -> f32 {
+        let distance = (a - b).rem_euclid(360.0);
+        distance.min(360.0 - distance)
+    }
+
+    #[test]
+    fn tones_get_lighter() {
+        let palette = CorePalette::from_seed(DOXA_SEED);
+        for tonal in [
+            palette.primary,
+            palette.secondary,
+            palette.tertiary,
+            palette.neutral,
+            palette.neutral_variant,
+            palette.error,
+        ] {
+            assert_eq!(tonal.tone(0), Rgb888::BLACK);
+            assert_eq!(tonal.tone(100), Rgb888::WHITE);
+            for tone in (0..100).step_by(5) {
+                assert!(
+                    luminance(tonal.tone(tone)) < luminance(tonal.tone(tone + 5)),
+                    "tone {} of {:?} isn't darker than tone {}",
+                    tone,
+                    tonal,
+                    tone + 5,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tones_above_100_are_white() {
+        assert_eq!(TonalPalette::new(120.0, 0.5).tone(150), Rgb888::WHITE);
+    }
+
+    #[test]
+    fn seed_hue_is_kept() {
+        for seed in [
+            DOXA_SEED,
+            Rgb888::new(255, 0, 0),
+            Rgb888::new(0, 200, 80),
+            Rgb888::new(255, 128, 0),
+            Rgb888::new(200, 0, 120),
+        ] {
+            let (hue, _) = hue_saturation(seed);
+            let palette = CorePalette::from_seed(seed);
+            for tone in [20, 40, 80] {
+                let (primary, _) = hue_saturation(palette.primary.tone(tone));
+                assert!(
+                    hue_distance(primary, hue) < 2.0,
+                    "tone {} of {:?} has hue {}, not {}",
+                    tone,
+                    seed,
+                    primary,
+                    hue,
+                );
+            }
+            let (tertiary, _) = hue_saturation(palette.tertiary.tone(40));
+            assert!(hue_distance(tertiary, hue + 60.0) < 2.0);
+        }
+    }
+
+    #[test]
+    fn hues_wrap_around() {
+        assert_eq!(TonalPalette::new(-30.0, 1.0), TonalPalette::new(330.0, 1.0));
+        assert_eq!(TonalPalette::new(400.0, 2.0), TonalPalette::new(40.0, 1.0));
+    }
+
+    /// Asserts that every color role and its "on" color can be read together,
+    /// at the WCAG AA ratio for normal text.
+    fn assert_on_colors_contrast(scheme: &ColorScheme) {
+        for (name, color, on) in [
+            ("primary", scheme.primary, scheme.on_primary),
+            (
+                "primary_container",
+                scheme.primary_container,
+                scheme.on_primary_container,
+            ),
+            ("secondary", scheme.secondary, scheme.on_secondary),
+            (
+                "secondary_container",
+                scheme.secondary_container,
+                scheme.on_secondary_container,
+            ),
+            ("tertiary", scheme.tertiary, scheme.on_tertiary),
+            (
+                "tertiary_container",
+                scheme.tertiary_container,
+                scheme.on_tertiary_container,
+            ),
+            ("error", scheme.error, scheme.on_error),
+            (
+                "error_container",
+                scheme.error_container,
+                scheme.on_error_container,
+            ),
+            ("surface", scheme.surface, scheme.on_surface),
+            (
+                "surface_variant",
+                scheme.surface_variant,
+                scheme.on_surface_variant,
+            ),
+        ] {
+            let ratio = contrast(color, on);
+            assert!(ratio >= 4.5, "{} has a contrast of {}", name, ratio);
+        }
+    }
+
+    #[test]
+    fn light_on_colors_contrast() {
+        for seed in SEEDS {
+            assert_on_colors_contrast(&Theme::from_seed(seed).light);
+        }
+    }
+
+    #[test]
+    fn dark_on_colors_contrast() {
+        for seed in SEEDS {
+            assert_on_colors_contrast(&Theme::from_seed(seed).dark);
+        }
+        assert_on_colors_contrast(&ColorScheme::DOXA_DARK);
+    }
+
+    #[test]
+    fn light_and_dark_surfaces_differ() {
+        for seed in SEEDS {
+            let theme = Theme::from_seed(seed);
+            assert!(luminance(theme.light.surface) > luminance(theme.light.on_surface));
+            assert!(luminance(theme.dark.surface) < luminance(theme.dark.on_surface));
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Fonts and text sizes used by the selector.
+
+use std::cell::RefCell;
+
+use rusttype::Font;
+
+use crate::FontError;
+
+/// A parsed font, shared by all typography made from the same font data
+pub(crate) type Face = &'static Font<'static>;
+
+thread_local! {
+    /// Faces parsed so far, by the address and length of their font data
+    static FACES: RefCell<Vec<((*const u8, usize), Face)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Text sizes in pixels for each text role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeScale {
+    /// Screen titles
+    pub heading: u32,
+    /// Route names and large buttons
+    pub body: u32,
+    /// Descriptions, small buttons and the bottom bar
+    pub caption: u32,
+}
+
+impl Default for TypeScale {
+    fn default() -> Self {
+        Self {
+            heading: 32,
+            body: 24,
+            caption: 18,
+        }
+    }
+}
+
+/// Fonts used by the selector's UI.
+///
+/// Text is drawn with the regular face, except headings, which use the bold
+/// face, and diagnostics values, which use the monospace face. Both fall back
+/// to the regular face if not set.
+///
+/// Characters that the face a string would be drawn with doesn't have are
+/// drawn with the first fallback face that has them, so route names with
+/// symbols or non-Latin characters don't render as boxes.
+///
+/// # Example
+///
+/// ```ignore
+/// let typography = Typography::new(include_bytes!("../assets/Inter-Regular.ttf"))?
+///     .bold(include_bytes!("../assets/Inter-Bold.ttf"))?
+///     .monospace(include_bytes!("../assets/JetBrainsMono-Regular.ttf"))?
+///     .fallback(include_bytes!("../assets/NotoSansJP-Regular.ttf"))?
+///     .scale(TypeScale { heading: 28, body: 20, caption: 16 });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Typography {
+    pub(crate) regular: Face,
+    pub(crate) bold: Option<Face>,
+    pub(crate) monospace: Option<Face>,
+    pub(crate) fallbacks: Vec<Face>,
+    pub(crate) scale: TypeScale,
+}
+
+impl Default for Typography {
+    /// Montserrat Regular at the default type scale.
+    fn default() -> Self {
+        // Note that Montserrat-Regular.ttf is actually a trimmed version of the
+        // full font to reduce binary size
+        Self::new(include_bytes!("../assets/Montserrat-Regular.ttf"))
+            .expect("embedded Montserrat font should be valid")
+    }
+}
+
+impl Typography {
+    /// Creates typography with the given TrueType or OpenType font as the
+    /// regular face.
+    pub fn new(regular: &'static [u8]) -> Result<Self, FontError> {
+        Ok(Self {
+            regular: parse(regular)?,
+            bold: None,
+            monospace: None,
+            fallbacks: Vec::new(),
+            scale: TypeScale::default(),
+        })
+    }
+
+    /// Sets the face used for headings.
+    pub fn bold(mut self, data: &'static [u8]) -> Result<Self, FontError> {
+        self.bold = Some(parse(data)?);
+        Ok(self)
+    }
+
+    /// Sets the face used for diagnostics values.
+    pub fn monospace(mut self, data: &'static [u8]) -> Result<Self, FontError> {
+        self.monospace = Some(parse(data)?);
+        Ok(self)
+    }
+
+    /// Adds a face to the end of the fallback chain.
+    pub fn fallback(mut self, data: &'static [u8]) -> Result<Self, FontError> {
+        self.fallbacks.push(parse(data)?);
+        Ok(self)
+    }
+
+    /// Sets the text sizes.
+    pub fn scale(mut self, scale: TypeScale) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+/// Parses a font, or returns the face already parsed from the same data.
+///
+/// Faces are leaked so that views, some of which must be `'static`, can borrow
+/// them freely. Since each font's data is only parsed once, that's one face
+/// per font, however often typography is created or replaced.
+fn parse(data: &'static [u8]) -> Result<Face, FontError> {
+    let key = (data.as_ptr(), data.len());
+    let parsed = FACES.with_borrow(|faces| {
+        faces
+            .iter()
+            .find(|(parsed, _)| *parsed == key)
+            .map(|&(_, face)| face)
+    });
+    if let Some(face) = parsed {
+        return Ok(face);
+    }
+    let face: Face = Box::leak(Box::new(Font::try_from_bytes(data).ok_or(FontError)?));
+    FACES.with_borrow_mut(|faces| faces.push((key, face)));
+    Ok(face)
+}
@@ -12,28 +12,27 @@ use buoyant::{
     render_target::{EmbeddedGraphicsRenderTarget, RenderTarget as _},
     view::prelude::*,
 };
+use embedded_graphics::prelude::DrawTarget;
 use embedded_touch::traits::TouchInputDevice;
 use unwrap_infallible::UnwrapInfallible;
 
 use crate::{
-    driver::DisplayTouchDriver,
+    driver::{ControllerDriver, DisplayTouchDriver},
     view::ui::{root_view, AppData, AppState},
+    CalibrationResult,
 };
 
 mod color;
 mod font;
+mod framebuffer;
 mod image;
+mod path;
+mod preview;
 mod spacing;
 mod ui;
 
-/// Target frames per second
-///
-/// The display can do 60 FPS, but to avoid unnecessary CPU usage, we limit to
-/// 30 FPS
-const FPS: u32 = 30;
-
-/// Duration of each frame
-const FRAME_DURATION: Duration = Duration::from_micros(1_000_000 / FPS as u64);
+pub use framebuffer::Framebuffer;
+pub use preview::{Preview, PreviewScreen};
 
 /// Minimum duration in between frames
 ///
@@ -41,20 +40,40 @@ const FRAME_DURATION: Duration = Duration::from_micros(1_000_000 / FPS as u64);
 /// between frames
 const MIN_FRAME_GAP: Duration = Duration::from_millis(5);
 
-/// Time before we stop rendering due to inactivity
+/// Lays out the current screen and renders it to `display`, with all
+/// animations finished.
 ///
-/// Maximum animation duration
-const INACTIVITY_TIMEOUT: Duration = Duration::from_secs(1);
+/// This draws both the brain's display in autonomous and previews, so they
+/// always match.
+fn render_frame<D>(app_data: &AppData, app_state: &mut AppState, display: &mut D)
+where
+    D: DrawTarget<Color = color::Color>,
+{
+    let scheme = *app_data.scheme();
+    let mut target = EmbeddedGraphicsRenderTarget::new_hinted(display, scheme.background);
+    target.clear(scheme.background);
+
+    let view = root_view(app_state, app_data);
+    let mut state = view.build_state(app_state);
+    let env = DefaultEnvironment::new(Duration::ZERO);
+    let layout = view.layout(&target.size().into(), &env, app_state, &mut state);
+    let tree = view.render_tree(&layout, Point::default(), &env, app_state, &mut state);
+    Render::render(&tree, &mut target, &scheme.primary_container);
+}
 
 pub async fn run<C: crate::route::Category, R: 'static>(
     display: vexide::display::Display,
+    controller: Option<vexide::controller::Controller>,
     external: Rc<RefCell<crate::ExternalState>>,
-    interface: impl crate::DoxaSelectInterface + 'static,
+    interface: Box<dyn crate::DoxaSelectInterface>,
+    config: crate::builder::Config,
     routes: Vec<crate::Route<C, R>>,
     categories: Vec<C>,
 ) {
     // DISPLAY RENDERING SETUP
 
+    let scheme = *config.theme.scheme();
+
     // Initialize display driver, which maps DrawTarget calls to the vexide Display API
     let mut display_driver = vexide_embedded_graphics::DisplayDriver::new(display);
     // Initialize another display to use for refreshing the screen
@@ -66,22 +85,32 @@ pub async fn run<C: crate::route::Category, R: 'static>(
         vexide::display::Display::new()
     };
     display.set_render_mode(vexide::display::RenderMode::DoubleBuffered);
-    // Create a new buoyant render target
-    let mut target =
-        EmbeddedGraphicsRenderTarget::new_hinted(&mut display_driver, color::M3_BACKGROUND);
+    // Views are laid out for the whole display
+    let size =
+        EmbeddedGraphicsRenderTarget::new_hinted(&mut display_driver, scheme.background).size();
 
     // DISPLAY TOUCH SETUP
     let mut touch_display = unsafe { vexide::display::Display::new() };
     let mut touch = DisplayTouchDriver::new(&mut touch_display);
 
+    // CONTROLLER SETUP
+    let mut controller = controller.map(ControllerDriver::new);
+
     // APPLICATION STATE SETUP
 
+    // Duration of each frame
+    let frame_duration = Duration::from_micros(1_000_000 / config.fps as u64);
+    let inactivity_timeout = config.inactivity_timeout;
+
     // Application start time for animations
     let app_start = Instant::now();
 
     // Initial application state
-    let mut app_state = AppState::new(external, interface);
-    let app_data = AppData::new(routes, categories);
+    let mut app_state = AppState::new(external, interface, vexide::competition::mode());
+    let app_data = AppData::new(routes, categories, config);
+    app_state.load_shortcuts(&app_data);
+    app_state.restore_selection(&app_data);
+    app_state.publish_confirm_blocker(&app_data);
 
     // Create the initial view and state
     let mut view = root_view(&app_state, &app_data);
@@ -90,7 +119,7 @@ pub async fn run<C: crate::route::Category, R: 'static>(
     // Create initial source and target trees for animation
     let time = app_start.elapsed();
     let env = DefaultEnvironment::new(time);
-    let layout = view.layout(&target.size().into(), &env, &mut app_state, &mut state);
+    let layout = view.layout(&size.into(), &env, &mut app_state, &mut state);
 
     let mut source_tree =
         &mut view.render_tree(&layout, Point::default(), &env, &mut app_state, &mut state);
@@ -102,6 +131,8 @@ pub async fn run<C: crate::route::Category, R: 'static>(
 
     // Store the last external state to detect changes
     let mut external_state = app_state.external.borrow().clone();
+    // Let the interface know about the initial (possibly restored) selection
+    app_state.notify_changes(None, &app_data);
     let mut previous_mode = vexide::competition::mode();
     let mut should_render = true;
 
@@ -110,43 +141,61 @@ pub async fn run<C: crate::route::Category, R: 'static>(
         {
             let interface = app_state.interface.as_ref();
             let mut external = app_state.external.borrow_mut();
+            let was_calibrating = external.calibrating;
             if interface.calibrating_enable() {
                 external.calibrating = interface.calibrating_calibrating();
             } else {
                 external.calibrating = false;
             }
+            if external.calibrating {
+                // Rounded so that the view is only rebuilt when the bar moves
+                external.calibration_progress = interface
+                    .calibrating_progress()
+                    .map(|progress| (progress.clamp(0.0, 1.0) * 1000.0) as u16);
+                external.calibration_status = interface.calibrating_status();
+            } else if was_calibrating {
+                let result = interface.calibrating_result();
+                // Only problems need the user's attention
+                app_state.calibration_alert = result != CalibrationResult::Success;
+                external.calibration = Some(result);
+            }
         }
 
         let frame_start = Instant::now();
 
         let mode = vexide::competition::mode();
+        app_state.mode = mode;
         let time = app_start.elapsed();
         let domain = AnimationDomain::top_level(time);
 
         if should_render {
             if mode == vexide::competition::CompetitionMode::Autonomous {
                 // In autonomous mode, we skip animations to save compute
-                Render::render(target_tree, &mut target, &color::M3_PRIMARY_CONTAINER);
+                render_frame(&app_data, &mut app_state, &mut display_driver);
             } else {
+                // Create a new buoyant render target, cleared for this frame
+                let mut target = EmbeddedGraphicsRenderTarget::new_hinted(
+                    &mut display_driver,
+                    scheme.background,
+                );
+                target.clear(scheme.background);
                 // Render animated transition between source and target trees
                 Render::render_animated(
                     &mut target,
                     source_tree,
                     target_tree,
-                    &color::M3_PRIMARY_CONTAINER,
+                    &scheme.primary_container,
                     &domain,
                 );
             }
             // Flush the rendered frame to the display
             display.render();
-            // Clear the render target for the next frame
-            target.clear(color::M3_BACKGROUND);
         }
 
         // For the next frame, determine if we should keep rendering
         if mode != vexide::competition::CompetitionMode::Autonomous {
             // In non-autonomous mode, we render until inactivity timeout
-            should_render = last_update.elapsed() < INACTIVITY_TIMEOUT;
+            should_render = last_update.elapsed() < inactivity_timeout;
         } else {
             // In autonomous mode, we don't render at all unless there's activity
             should_render = false;
@@ -161,19 +210,61 @@ pub async fn run<C: crate::route::Category, R: 'static>(
             .map(|e| Event::Touch(e.clone()));
         // Diff external state to generate synthetic events if needed
         let synthetic_events = {
-            let current_external = app_state.external.borrow();
             let mut events = Vec::new();
-            if *current_external != external_state {
+            // Controller navigation is applied directly to the app state since
+            // it doesn't go through buoyant's event handling
+            if let Some(controller) = &mut controller {
+                for action in controller.actions() {
+                    app_state.handle_controller(action, &app_data);
+                    events.push(Event::External);
+                }
+            }
+            // Step the robot marker along the route's path preview
+            if app_state.advance_path_preview(&app_data) {
+                events.push(Event::External);
+            }
+            // Pick up changes to the checklist items the robot checks
+            if app_state.refresh_checklist(&app_data) {
                 events.push(Event::External);
-                external_state = current_external.clone();
+            }
+            // Let `DoxaSelect::confirm` know whether confirming is blocked
+            app_state.publish_confirm_blocker(&app_data);
+            // Pick up changes to the interface's quick actions
+            if app_state.refresh_actions() {
+                events.push(Event::External);
+            }
+            // Keep the time since confirmation current
+            if app_state.tick_confirmed_clock() {
+                events.push(Event::External);
+            }
+            // Keep live diagnostics and their history up to date, rebuilding
+            // only if the diagnostics screen shows something that changed
+            if app_state.poll_diagnostics(&app_data) {
+                events.push(Event::External);
+            }
+            // Handle screen changes requested through the DoxaSelect API
+            let request = app_state.external.borrow_mut().request.take();
+            if let Some(request) = request {
+                app_state.handle_request(request, &app_data);
+                events.push(Event::External);
+            }
+            let current_external = app_state.external.borrow().clone();
+            if current_external != external_state {
+                events.push(Event::External);
+                app_state.notify_changes(Some(&external_state), &app_data);
+                external_state = current_external;
             }
             if previous_mode != mode {
                 if mode == vexide::competition::CompetitionMode::Autonomous {
                     // Switch to confirmed screen in autonomous mode, since
                     // that means that the match has started
                     app_state.screen = crate::view::ui::Screen::Confirmed;
-                    events.push(Event::External);
+                    // The checklist was for this match
+                    app_state.reset_checklist();
                 }
+                // Some views depend on the competition mode, such as the
+                // post-autonomous error report
+                events.push(Event::External);
                 previous_mode = mode;
             }
             events
@@ -191,7 +282,7 @@ pub async fn run<C: crate::route::Category, R: 'static>(
                 // Create new view and target tree
                 view = root_view(&app_state, &app_data);
                 let env = DefaultEnvironment::new(time);
-                let layout = view.layout(&target.size().into(), &env, &mut app_state, &mut state);
+                let layout = view.layout(&size.into(), &env, &mut app_state, &mut state);
                 *target_tree =
                     view.render_tree(&layout, Point::default(), &env, &mut app_state, &mut state);
                 should_render = true;
@@ -202,12 +293,19 @@ pub async fn run<C: crate::route::Category, R: 'static>(
         // need to re-render for internally-generated changes.
         // We know the view changed it and not external code because there's no
         // await points here.
-        if *app_state.external.borrow() != external_state {
-            external_state = app_state.external.borrow().clone();
+        let current_external = app_state.external.borrow().clone();
+        if current_external != external_state {
+            app_state.notify_changes(Some(&external_state), &app_data);
+            external_state = current_external;
+        }
+
+        // Mirror the current screen to the controller
+        if let Some(controller) = &mut controller {
+            controller.mirror(&app_state.controller_lines(&app_data));
         }
 
         let elapsed = frame_start.elapsed();
-        let sleep_time = Duration::max(FRAME_DURATION.saturating_sub(elapsed), MIN_FRAME_GAP);
+        let sleep_time = Duration::max(frame_duration.saturating_sub(elapsed), MIN_FRAME_GAP);
         // Throttle to maintain constant FPS
         vexide::time::sleep(sleep_time).await;
     }
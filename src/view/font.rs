@@ -1,12 +1,313 @@
-use std::sync::LazyLock;
-
-pub static MONTSERRAT: LazyLock<rusttype::Font<'static>> = LazyLock::new(|| {
-    // Include the font data at compile time
-    // Note that Montserrat-Regular.ttf is actually a trimmed version of the
-    // full font to reduce binary size
-    rusttype::Font::try_from_bytes(include_bytes!("../../assets/Montserrat-Regular.ttf")).unwrap()
-});
-
-pub const SIZE_BODY: u32 = 24;
-pub const SIZE_CAPTION: u32 = 18;
-pub const SIZE_HEADING: u32 = 32;
+use std::cell::RefCell;
+
+use buoyant::{
+    match_view,
+    view::{prelude::*, ForEach},
+};
+use rusttype::{Font, Scale};
+
+use crate::{
+    typography::{Face, TypeScale, Typography},
+    view::color,
+};
+
+/// Most lines drawn of text that needs more than one face
+const MAX_LINES: usize = 16;
+
+/// Most runs on one line of text that needs more than one face. Lines with
+/// more runs carry on onto the next line.
+const MAX_LINE_RUNS: usize = 16;
+
+/// Line and run indices for `ForEach`, which requires a slice that outlives
+/// the view
+static INDICES: [usize; MAX_LINES] = {
+    let mut indices = [0; MAX_LINES];
+    let mut i = 0;
+    while i < MAX_LINES {
+        indices[i] = i;
+        i += 1;
+    }
+    indices
+};
+
+thread_local! {
+    /// Fallback chains in use, so each distinct chain is only leaked once
+    static CHAINS: RefCell<Vec<&'static [Face]>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Fonts in use by the UI.
+///
+/// The faces themselves are shared by all typography made from the same font
+/// data (see [`Typography`]), and the fallback chain is interned here, so
+/// replacing the typography with fonts that were loaded before doesn't use any
+/// more memory.
+#[derive(Debug, Clone, Copy)]
+pub struct Fonts {
+    pub regular: Face,
+    pub heading: Face,
+    pub monospace: Face,
+    fallbacks: &'static [Face],
+    pub scale: TypeScale,
+}
+
+impl Fonts {
+    pub fn new(typography: &Typography) -> Self {
+        let regular = typography.regular;
+        Self {
+            regular,
+            heading: typography.bold.unwrap_or(regular),
+            monospace: typography.monospace.unwrap_or(regular),
+            fallbacks: intern(&typography.fallbacks),
+            scale: typography.scale,
+        }
+    }
+
+    /// Returns the face to draw `c` with, preferring `face`.
+    ///
+    /// If `face` is missing `c`, the first fallback face that has it is used.
+    /// If none do, `face` is used anyway.
+    fn for_char(&self, face: Face, c: char) -> Face {
+        if c.is_whitespace() || has_glyph(face, c) {
+            return face;
+        }
+        self.fallbacks
+            .iter()
+            .copied()
+            .find(|fallback| has_glyph(fallback, c))
+            .unwrap_or(face)
+    }
+
+    /// Splits `text` into runs of characters that are drawn with the same
+    /// face, preferring `face`.
+    ///
+    /// Whitespace stays in the run it follows. There's always at least one
+    /// run.
+    pub fn runs(&self, face: Face, text: &str) -> Vec<(Face, String)> {
+        let mut runs: Vec<(Face, String)> = Vec::new();
+        for c in text.chars() {
+            let font = self.for_char(face, c);
+            match runs.last_mut() {
+                Some((run_font, run)) if c.is_whitespace() || std::ptr::eq(*run_font, font) => {
+                    run.push(c);
+                }
+                _ => runs.push((font, c.to_string())),
+            }
+        }
+        if runs.is_empty() {
+            runs.push((face, String::new()));
+        }
+        runs
+    }
+
+    /// Width in pixels of `text` drawn at `size`, preferring `face`.
+    pub fn measure(&self, face: Face, size: u32, text: &str) -> f32 {
+        text.chars().map(|c| self.advance(face, size, c)).sum()
+    }
+
+    /// Width in pixels that `c` takes up when drawn at `size`, preferring
+    /// `face`.
+    fn advance(&self, face: Face, size: u32, c: char) -> f32 {
+        self.for_char(face, c)
+            .glyph(c)
+            .scaled(Scale::uniform(size as f32))
+            .h_metrics()
+            .advance_width
+    }
+
+    /// Draws `text` preferring `face`, with each character falling back to
+    /// the first fallback face that has it.
+    ///
+    /// `run_view` builds the view of each run from its text and face, so
+    /// every run is styled the same. Text that only needs one face is a single
+    /// run, which wraps like any other text. Text that needs several is only
+    /// broken onto new lines at line breaks; use [`Fonts::wrapped_text`] for
+    /// text that may not fit on one line.
+    pub fn text<C, V>(
+        &self,
+        face: Face,
+        text: &str,
+        run_view: impl Fn(String, Face) -> V + Copy,
+    ) -> impl View<color::Color, C>
+    where
+        V: View<color::Color, C>,
+    {
+        self.lines(face, text, None, run_view)
+    }
+
+    /// Draws `text` like [`Fonts::text`], wrapping it at word boundaries to
+    /// fit within `width` when drawn at `size`.
+    pub fn wrapped_text<C, V>(
+        &self,
+        face: Face,
+        size: u32,
+        width: u32,
+        text: &str,
+        run_view: impl Fn(String, Face) -> V + Copy,
+    ) -> impl View<color::Color, C>
+    where
+        V: View<color::Color, C>,
+    {
+        self.lines(face, text, Some((size, width)), run_view)
+    }
+
+    /// Draws `text` as one view if it only needs one face, or otherwise as a
+    /// stack of lines of runs, broken to fit within the width of `wrap`.
+    fn lines<C, V>(
+        &self,
+        face: Face,
+        text: &str,
+        wrap: Option<(u32, u32)>,
+        run_view: impl Fn(String, Face) -> V + Copy,
+    ) -> impl View<color::Color, C>
+    where
+        V: View<color::Color, C>,
+    {
+        let runs = self.runs(face, text);
+        let single = runs.len() == 1;
+        let first_font = runs[0].0;
+        let lines = if single {
+            Vec::new()
+        } else {
+            let width = wrap.map(|(_, width)| width as f32);
+            let size = wrap.map_or(0, |(size, _)| size);
+            break_lines(text, width, |c| self.advance(face, size, c))
+                .iter()
+                .flat_map(|line| {
+                    self.runs(face, line)
+                        .chunks(MAX_LINE_RUNS)
+                        .map(<[_]>::to_vec)
+                        .collect::<Vec<_>>()
+                })
+                .take(MAX_LINES)
+                .collect::<Vec<_>>()
+        };
+        let text = text.to_string();
+        match_view!(single, {
+            true => run_view(text, first_font),
+            false => {
+                let len = lines.len();
+                ForEach::<MAX_LINES>::new_vertical(&INDICES[..len], move |line: &usize| {
+                    let runs = lines[*line].clone();
+                    let len = runs.len();
+                    ForEach::<MAX_LINE_RUNS>::new_horizontal(&INDICES[..len], move |run: &usize| {
+                        let (font, text) = runs[*run].clone();
+                        run_view(text, font)
+                    })
+                })
+            },
+        })
+    }
+}
+
+/// Returns the interned copy of a fallback chain, leaking it the first time
+/// it's seen.
+fn intern(fallbacks: &[Face]) -> &'static [Face] {
+    CHAINS.with_borrow_mut(|chains| {
+        let same = |chain: &&'static [Face]| {
+            chain.len() == fallbacks.len()
+                && chain
+                    .iter()
+                    .zip(fallbacks)
+                    .all(|(a, b)| std::ptr::eq(*a, *b))
+        };
+        if let Some(chain) = chains.iter().find(|chain| same(chain)) {
+            return *chain;
+        }
+        let chain: &'static [Face] = Vec::leak(fallbacks.to_vec());
+        chains.push(chain);
+        chain
+    })
+}
+
+/// Breaks `text` into lines at line breaks, and at word boundaries so that
+/// each line fits within `width`, given the width of each character.
+///
+/// Words that don't fit on a line of their own, including text in scripts
+/// that don't separate words with spaces, are broken between any two
+/// characters. Spaces at the end of a line don't count towards its width.
+fn break_lines(text: &str, width: Option<f32>, advance: impl Fn(char) -> f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0.0;
+    // Where the line can be broken, just after its last space
+    let mut break_at = None;
+    for c in text.chars() {
+        if c == '\n' {
+            lines.push(std::mem::take(&mut line));
+            line_width = 0.0;
+            break_at = None;
+            continue;
+        }
+        let c_width = advance(c);
+        let overflows = width.is_some_and(|width| line_width + c_width > width);
+        if overflows && !c.is_whitespace() && !line.is_empty() {
+            let rest = match break_at.take() {
+                Some(index) => line.split_off(index),
+                None => String::new(),
+            };
+            lines.push(std::mem::replace(&mut line, rest).trim_end().to_string());
+            line_width = line.chars().map(&advance).sum();
+        }
+        line.push(c);
+        line_width += c_width;
+        if c.is_whitespace() {
+            break_at = Some(line.len());
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+/// Whether `font` has a glyph for `c`.
+fn has_glyph(font: &Font<'_>, c: char) -> bool {
+    font.glyph(c).id().0 != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Breaks lines as if every character were 1 pixel wide
+    fn lines(text: &str, width: f32) -> Vec<String> {
+        break_lines(text, Some(width), |_| 1.0)
+    }
+
+    #[test]
+    fn breaks_at_word_boundaries() {
+        assert_eq!(lines("Left side AWP", 9.0), ["Left side", "AWP"]);
+        assert_eq!(lines("Left side AWP", 4.0), ["Left", "side", "AWP"]);
+        // Trailing spaces fit on the line they end
+        assert_eq!(lines("Left side", 4.0), ["Left", "side"]);
+    }
+
+    #[test]
+    fn breaks_long_words_anywhere() {
+        assert_eq!(lines("Skills", 4.0), ["Skil", "ls"]);
+        assert_eq!(lines("左側の自動", 2.0), ["左側", "の自", "動"]);
+        assert_eq!(lines("Go 左側の自動", 4.0), ["Go", "左側の自", "動"]);
+    }
+
+    #[test]
+    fn keeps_line_breaks() {
+        assert_eq!(lines("Left\nRight", 10.0), ["Left", "Right"]);
+        assert_eq!(
+            break_lines("Left side\nRight", None, |_| 1.0),
+            ["Left side", "Right"]
+        );
+        assert_eq!(break_lines("", None, |_| 1.0), [""]);
+    }
+
+    #[test]
+    fn fits_without_breaking() {
+        assert_eq!(lines("Left side AWP", 13.0), ["Left side AWP"]);
+    }
+
+    #[test]
+    fn reuses_faces_and_chains() {
+        let typography = Typography::default();
+        let fonts = Fonts::new(&typography);
+        let again = Fonts::new(&Typography::default());
+        assert!(std::ptr::eq(fonts.regular, again.regular));
+        assert!(std::ptr::eq(fonts.fallbacks, again.fallbacks));
+    }
+}
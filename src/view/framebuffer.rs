@@ -0,0 +1,290 @@
+use std::convert::Infallible;
+
+use embedded_graphics::{
+    pixelcolor::RgbColor,
+    prelude::{DrawTarget, OriginDimensions, Pixel, Size},
+};
+
+use super::color::Color;
+
+/// Width of the V5 brain's display in pixels
+pub const WIDTH: u32 = 480;
+/// Height of the V5 brain's display in pixels, excluding the header bar
+pub const HEIGHT: u32 = 240;
+
+/// In-memory framebuffer with the same size as the V5 brain's display.
+///
+/// This implements [`DrawTarget`], so the selector's UI can be rendered into it
+/// on a host machine without a brain. Frames can be exported as BMP or PNG
+/// images with [`Framebuffer::to_bmp`] and [`Framebuffer::to_png`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Framebuffer {
+    pixels: Vec<Color>,
+}
+
+impl Default for Framebuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Framebuffer {
+    /// Creates a black framebuffer.
+    pub fn new() -> Self {
+        Self {
+            pixels: vec![Color::BLACK; (WIDTH * HEIGHT) as usize],
+        }
+    }
+
+    /// Returns the color of the pixel at the given coordinates, or `None` if
+    /// they're out of bounds.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Color> {
+        (x < WIDTH && y < HEIGHT).then(|| self.pixels[(y * WIDTH + x) as usize])
+    }
+
+    /// Returns all pixels in row-major order.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    /// Encodes the framebuffer as a 24-bit BMP image.
+    pub fn to_bmp(&self) -> Vec<u8> {
+        const HEADER_SIZE: u32 = 14 + 40;
+        // Rows are padded to a multiple of 4 bytes
+        let row_size = (WIDTH * 3).div_ceil(4) * 4;
+        let image_size = row_size * HEIGHT;
+
+        let mut bmp = Vec::with_capacity((HEADER_SIZE + image_size) as usize);
+        // File header
+        bmp.extend_from_slice(b"BM");
+        bmp.extend_from_slice(&(HEADER_SIZE + image_size).to_le_bytes());
+        bmp.extend_from_slice(&[0; 4]);
+        bmp.extend_from_slice(&HEADER_SIZE.to_le_bytes());
+        // BITMAPINFOHEADER
+        bmp.extend_from_slice(&40u32.to_le_bytes());
+        bmp.extend_from_slice(&(WIDTH as i32).to_le_bytes());
+        bmp.extend_from_slice(&(HEIGHT as i32).to_le_bytes());
+        bmp.extend_from_slice(&1u16.to_le_bytes());
+        bmp.extend_from_slice(&24u16.to_le_bytes());
+        bmp.extend_from_slice(&0u32.to_le_bytes());
+        bmp.extend_from_slice(&image_size.to_le_bytes());
+        bmp.extend_from_slice(&2835i32.to_le_bytes());
+        bmp.extend_from_slice(&2835i32.to_le_bytes());
+        bmp.extend_from_slice(&[0; 8]);
+        // Pixel data, bottom row first and in BGR order
+        for row in self.pixels.chunks(WIDTH as usize).rev() {
+            for pixel in row {
+                bmp.extend_from_slice(&[pixel.b(), pixel.g(), pixel.r()]);
+            }
+            bmp.resize(bmp.len() + (row_size - WIDTH * 3) as usize, 0);
+        }
+        bmp
+    }
+
+    /// Encodes the framebuffer as an 8-bit RGB PNG image.
+    ///
+    /// The image data is stored uncompressed to avoid pulling in a compression
+    /// library, so the files are about as large as the equivalent BMP.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(((WIDTH * 3 + 1) * HEIGHT) as usize);
+        for row in self.pixels.chunks(WIDTH as usize) {
+            // Filter type "none"
+            raw.push(0);
+            for pixel in row {
+                raw.extend_from_slice(&[pixel.r(), pixel.g(), pixel.b()]);
+            }
+        }
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&WIDTH.to_be_bytes());
+        ihdr.extend_from_slice(&HEIGHT.to_be_bytes());
+        // Bit depth 8, color type RGB, default compression, filter and interlace
+        ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut png, b"IHDR", &ihdr);
+        png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+impl OriginDimensions for Framebuffer {
+    fn size(&self) -> Size {
+        Size::new(WIDTH, HEIGHT)
+    }
+}
+
+impl DrawTarget for Framebuffer {
+    type Color = Color;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let (Ok(x), Ok(y)) = (u32::try_from(point.x), u32::try_from(point.y)) {
+                if x < WIDTH && y < HEIGHT {
+                    self.pixels[(y * WIDTH + x) as usize] = color;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Appends a PNG chunk with its length and CRC.
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wraps data in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+
+    let mut out = Vec::with_capacity(data.len() + data.len() / MAX_BLOCK * 5 + 11);
+    // Deflate with a 32K window, no preset dictionary
+    out.extend_from_slice(&[0x78, 0x01]);
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        out.push(is_final as u8);
+        out.extend_from_slice(&(block.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), byte| {
+        let a = (a + *byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::prelude::Point;
+    use tinybmp::Bmp;
+
+    use super::*;
+
+    /// Framebuffer with a few distinct pixels, including in the corners so that
+    /// flipped or offset rows show up.
+    fn sample() -> Framebuffer {
+        let mut framebuffer = Framebuffer::new();
+        framebuffer
+            .draw_iter([
+                Pixel(Point::new(0, 0), Color::new(255, 0, 0)),
+                Pixel(Point::new(WIDTH as i32 - 1, 0), Color::new(0, 255, 0)),
+                Pixel(Point::new(0, HEIGHT as i32 - 1), Color::new(0, 0, 255)),
+                Pixel(Point::new(123, 45), Color::new(12, 34, 56)),
+            ])
+            .unwrap();
+        framebuffer
+    }
+
+    /// Reassembles the data of all chunks of the given kind, checking each
+    /// chunk's CRC along the way.
+    fn png_chunks(png: &[u8], kind: &[u8; 4]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let len = u32::from_be_bytes(rest[0..4].try_into().unwrap()) as usize;
+            let body = &rest[4..8 + len];
+            let crc = u32::from_be_bytes(rest[8 + len..12 + len].try_into().unwrap());
+            assert_eq!(crc32(body), crc, "bad CRC in {:?} chunk", &body[..4]);
+            if &body[..4] == kind {
+                data.extend_from_slice(&body[4..]);
+            }
+            rest = &rest[12 + len..];
+        }
+        data
+    }
+
+    /// Unwraps a zlib stream made of uncompressed deflate blocks.
+    fn unzlib_stored(zlib: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut rest = &zlib[2..];
+        loop {
+            let is_final = rest[0] & 1 == 1;
+            let len = u16::from_le_bytes([rest[1], rest[2]]) as usize;
+            assert_eq!(u16::from_le_bytes([rest[3], rest[4]]), !(len as u16));
+            data.extend_from_slice(&rest[5..5 + len]);
+            rest = &rest[5 + len..];
+            if is_final {
+                break;
+            }
+        }
+        assert_eq!(rest, adler32(&data).to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn bmp_round_trips() {
+        let framebuffer = sample();
+        let bmp = framebuffer.to_bmp();
+        assert_eq!(&bmp[0..2], b"BM");
+        assert_eq!(
+            u32::from_le_bytes(bmp[2..6].try_into().unwrap()) as usize,
+            bmp.len()
+        );
+
+        let decoded = Bmp::<Color>::from_slice(&bmp).unwrap();
+        assert_eq!(decoded.size(), Size::new(WIDTH, HEIGHT));
+        let mut pixels = Framebuffer::new();
+        pixels.draw_iter(decoded.pixels()).unwrap();
+        assert_eq!(pixels, framebuffer);
+    }
+
+    #[test]
+    fn png_round_trips() {
+        let framebuffer = sample();
+        let png = framebuffer.to_png();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let ihdr = png_chunks(&png, b"IHDR");
+        assert_eq!(u32::from_be_bytes(ihdr[0..4].try_into().unwrap()), WIDTH);
+        assert_eq!(u32::from_be_bytes(ihdr[4..8].try_into().unwrap()), HEIGHT);
+        assert_eq!(&ihdr[8..], &[8, 2, 0, 0, 0]);
+        assert!(png_chunks(&png, b"IEND").is_empty());
+
+        let raw = unzlib_stored(&png_chunks(&png, b"IDAT"));
+        let rows = raw.chunks(WIDTH as usize * 3 + 1).collect::<Vec<_>>();
+        assert_eq!(rows.len(), HEIGHT as usize);
+        for (y, row) in rows.iter().enumerate() {
+            assert_eq!(row[0], 0, "row {y} should have no filter");
+            for (x, rgb) in row[1..].chunks(3).enumerate() {
+                let pixel = framebuffer.pixel(x as u32, y as u32).unwrap();
+                assert_eq!(rgb, [pixel.r(), pixel.g(), pixel.b()], "pixel ({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn checksums_match_reference_values() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+}
@@ -1,54 +1,318 @@
-use std::{fs, sync::LazyLock};
+use std::{convert::Infallible, fs};
 
+use embedded_graphics::{
+    image::{ImageDrawable, ImageRaw},
+    prelude::*,
+    primitives::Rectangle,
+};
 use tinybmp::Bmp;
+use unwrap_infallible::UnwrapInfallible;
 
-use super::color;
+use super::{
+    color,
+    framebuffer::{HEIGHT, WIDTH},
+};
+use crate::branding::{ImageFit, ImageSource};
 
-static SELECTED_BACKGROUND_RAW: LazyLock<Option<Vec<u8>>> = LazyLock::new(|| {
-    let raw = fs::read("selected-background.bmp");
-    match raw {
-        Ok(data) => Some(data),
-        Err(e) => {
-            log::warn!("Failed to read selected-background.bmp: {}", e);
-            None
+mod qoi;
+
+/// Decoded image, one color per pixel in row-major order.
+///
+/// Images that are redrawn while the selector runs, such as path previews,
+/// are drawn straight from their pixels so that they can be dropped when
+/// they're replaced.
+#[derive(Debug)]
+pub struct Pixels {
+    width: u32,
+    height: u32,
+    data: Vec<color::Color>,
+}
+
+impl Pixels {
+    /// Creates an image filled with a single color.
+    pub fn new(width: u32, height: u32, color: color::Color) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![color; (width * height) as usize],
+        }
+    }
+
+    fn get(&self, x: u32, y: u32) -> color::Color {
+        self.data[(y * self.width + x) as usize]
+    }
+
+    /// Resizes the image with nearest-neighbor sampling.
+    pub fn resize(&self, width: u32, height: u32) -> Self {
+        let mut data = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            let src_y = (y as u64 * self.height as u64 / height as u64) as u32;
+            for x in 0..width {
+                let src_x = (x as u64 * self.width as u64 / width as u64) as u32;
+                data.push(self.get(src_x, src_y));
+            }
+        }
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Cuts the image down to at most the given size, keeping the center.
+    fn crop(&self, width: u32, height: u32) -> Self {
+        let width = width.min(self.width);
+        let height = height.min(self.height);
+        let left = (self.width - width) / 2;
+        let top = (self.height - height) / 2;
+        let mut data = Vec::with_capacity((width * height) as usize);
+        for y in top..top + height {
+            for x in left..left + width {
+                data.push(self.get(x, y));
+            }
         }
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Scales and crops the image to the display as described by `fit`.
+    fn fit(self, fit: ImageFit) -> Self {
+        // Whether the image is wider than the display, relative to its height
+        let wider = self.width as u64 * HEIGHT as u64 > self.height as u64 * WIDTH as u64;
+        match fit {
+            ImageFit::Original => self,
+            ImageFit::Crop => self.crop(WIDTH, HEIGHT),
+            ImageFit::Contain => {
+                let (width, height) = if wider {
+                    (WIDTH, (self.height * WIDTH / self.width).max(1))
+                } else {
+                    ((self.width * HEIGHT / self.height).max(1), HEIGHT)
+                };
+                self.resize(width, height)
+            }
+            ImageFit::Cover => {
+                let (width, height) = if wider {
+                    ((self.width * HEIGHT / self.height).max(1), HEIGHT)
+                } else {
+                    (WIDTH, (self.height * WIDTH / self.width).max(1))
+                };
+                self.resize(width, height).crop(WIDTH, HEIGHT)
+            }
+        }
+    }
+
+    /// Converts the image to a drawable, leaking its contents.
+    pub fn leak(self) -> ImageRaw<'static, color::Color> {
+        let bytes = self
+            .data
+            .iter()
+            .flat_map(|color| [color.r(), color.g(), color.b()])
+            .collect::<Vec<u8>>();
+        ImageRaw::new(Vec::leak(bytes), self.width)
+    }
+}
+
+impl OriginDimensions for Pixels {
+    fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+}
+
+impl ImageDrawable for Pixels {
+    type Color = color::Color;
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        target.fill_contiguous(&self.bounding_box(), self.data.iter().copied())
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        // Draw the whole image shifted so the area starts at the origin, and
+        // clipped to the area
+        self.draw(&mut target.translated(-area.top_left).clipped(area))
     }
-});
+}
 
-pub static SELECTED_BACKGROUND: LazyLock<Option<Bmp<color::Color>>> =
-    LazyLock::new(|| match &*SELECTED_BACKGROUND_RAW {
-        Some(raw) => match Bmp::from_slice(raw) {
-            Ok(bmp) => Some(bmp),
-            Err(e) => {
-                log::error!(
-                    "Failed to parse selected-background.bmp as BMP image: {:?}",
-                    e
-                );
-                None
+impl DrawTarget for Pixels {
+    type Color = color::Color;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Ok((x, y)) = <(u32, u32)>::try_from(point) {
+                if x < self.width && y < self.height {
+                    self.data[(y * self.width + x) as usize] = color;
+                }
             }
-        },
-        None => None,
-    });
-
-static LOGO_CROPPED_RAW: LazyLock<Option<Vec<u8>>> = LazyLock::new(|| {
-    let raw = fs::read("logo-cropped.bmp");
-    match raw {
-        Ok(data) => Some(data),
+        }
+        Ok(())
+    }
+}
+
+/// Decodes a BMP or QOI image, telling them apart by their magic bytes.
+fn decode(data: &[u8]) -> Result<Pixels, String> {
+    if data.starts_with(qoi::MAGIC) {
+        return qoi::decode(data).map_err(|e| e.to_string());
+    }
+
+    let bmp = Bmp::<color::Color>::from_slice(data).map_err(|e| format!("{e:?}"))?;
+    let Size { width, height } = bmp.size();
+    let mut pixels = Pixels::new(width, height, color::Color::BLACK);
+    pixels.draw_iter(bmp.pixels()).unwrap_infallible();
+    Ok(pixels)
+}
+
+/// Loads a BMP or QOI image from the given source and fits it to the display.
+///
+/// Images are loaded once at startup and live for the rest of the program, so
+/// the decoded pixels are leaked to give the image a `'static` lifetime.
+pub fn load(source: &ImageSource, fit: ImageFit) -> Option<ImageRaw<'static, color::Color>> {
+    read(source).map(|pixels| pixels.fit(fit).leak())
+}
+
+/// Reads a file from the SD card.
+///
+/// If a `.qoi` file is missing, the `.bmp` file with the same name is read
+/// instead, since the default images used to be BMPs and SD cards set up for
+/// older versions of the crate only have those. Returns the path that was read.
+fn read_file(path: &str) -> Option<(String, Vec<u8>)> {
+    let error = match fs::read(path) {
+        Ok(data) => return Some((path.to_string(), data)),
+        Err(e) => e,
+    };
+    if let Some(stem) = path.strip_suffix(".qoi") {
+        let bmp = format!("{stem}.bmp");
+        if let Ok(data) = fs::read(&bmp) {
+            log::warn!(
+                "{} is missing, so the older {} was loaded instead. Copy the QOI images from the assets folder to the SD card",
+                path,
+                bmp
+            );
+            return Some((bmp, data));
+        }
+    }
+    log::warn!("Failed to read {}: {}", path, error);
+    None
+}
+
+/// Reads and decodes a BMP or QOI image from the given source.
+pub fn read(source: &ImageSource) -> Option<Pixels> {
+    let (name, decoded) = match source {
+        ImageSource::Path(path) => {
+            let (path, data) = read_file(path)?;
+            (path, decode(&data))
+        }
+        ImageSource::Bytes(bytes) => ("embedded image".to_string(), decode(bytes)),
+        ImageSource::None => return None,
+    };
+    match decoded {
+        Ok(pixels) => Some(pixels),
         Err(e) => {
-            log::warn!("Failed to read logo-cropped.bmp: {}", e);
+            log::error!("Failed to decode {} as BMP or QOI image: {}", name, e);
             None
         }
     }
-});
+}
 
-pub static LOGO_CROPPED: LazyLock<Option<Bmp<color::Color>>> =
-    LazyLock::new(|| match &*LOGO_CROPPED_RAW {
-        Some(raw) => match Bmp::from_slice(raw) {
-            Ok(bmp) => Some(bmp),
-            Err(e) => {
-                log::error!("Failed to parse logo-cropped.bmp as BMP image: {:?}", e);
-                None
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: color::Color = color::Color::RED;
+    const BLUE: color::Color = color::Color::BLUE;
+
+    /// Image with a red left half and a blue right half.
+    fn halves(width: u32, height: u32) -> Pixels {
+        let mut pixels = Pixels::new(width, height, RED);
+        for y in 0..height {
+            for x in width / 2..width {
+                pixels.data[(y * width + x) as usize] = BLUE;
             }
-        },
-        None => None,
-    });
+        }
+        pixels
+    }
+
+    fn fitted_size(width: u32, height: u32, fit: ImageFit) -> Size {
+        halves(width, height).fit(fit).size()
+    }
+
+    #[test]
+    fn draws_sub_images() {
+        let image = halves(4, 2);
+        let mut target = Pixels::new(2, 2, color::Color::BLACK);
+        image
+            .draw_sub_image(
+                &mut target,
+                &Rectangle::new(Point::new(1, 1), Size::new(2, 1)),
+            )
+            .unwrap_infallible();
+        assert_eq!(target.get(0, 0), RED);
+        assert_eq!(target.get(1, 0), BLUE);
+        // Nothing outside the area is drawn
+        assert_eq!(target.get(0, 1), color::Color::BLACK);
+        assert_eq!(target.get(1, 1), color::Color::BLACK);
+    }
+
+    #[test]
+    fn fits_wide_images() {
+        assert_eq!(
+            fitted_size(960, 240, ImageFit::Original),
+            Size::new(960, 240)
+        );
+        assert_eq!(fitted_size(960, 240, ImageFit::Crop), Size::new(480, 240));
+        assert_eq!(
+            fitted_size(960, 240, ImageFit::Contain),
+            Size::new(480, 120)
+        );
+        assert_eq!(fitted_size(960, 240, ImageFit::Cover), Size::new(480, 240));
+
+        // Cropping keeps the center, where the halves meet
+        let cropped = halves(960, 240).fit(ImageFit::Crop);
+        assert_eq!(cropped.get(0, 0), RED);
+        assert_eq!(cropped.get(479, 239), BLUE);
+    }
+
+    #[test]
+    fn fits_tall_images() {
+        assert_eq!(
+            fitted_size(240, 480, ImageFit::Original),
+            Size::new(240, 480)
+        );
+        assert_eq!(fitted_size(240, 480, ImageFit::Crop), Size::new(240, 240));
+        assert_eq!(
+            fitted_size(240, 480, ImageFit::Contain),
+            Size::new(120, 240)
+        );
+        assert_eq!(fitted_size(240, 480, ImageFit::Cover), Size::new(480, 240));
+    }
+
+    #[test]
+    fn fits_exact_images() {
+        for fit in [
+            ImageFit::Original,
+            ImageFit::Crop,
+            ImageFit::Contain,
+            ImageFit::Cover,
+        ] {
+            let fitted = halves(WIDTH, HEIGHT).fit(fit);
+            assert_eq!(fitted.size(), Size::new(WIDTH, HEIGHT), "{fit:?}");
+            assert_eq!(fitted.data, halves(WIDTH, HEIGHT).data, "{fit:?}");
+        }
+    }
+
+    #[test]
+    fn crops_small_images_to_themselves() {
+        assert_eq!(fitted_size(100, 50, ImageFit::Crop), Size::new(100, 50));
+    }
+}
@@ -0,0 +1,152 @@
+//! Decoder for the [QOI image format](https://qoiformat.org/qoi-specification.pdf).
+//!
+//! QOI compresses about as well as PNG for logos and flat backgrounds, but is
+//! simple and fast enough to decode on the brain.
+
+use super::Pixels;
+use crate::view::color::Color;
+
+/// Magic bytes at the start of every QOI file
+pub const MAGIC: &[u8; 4] = b"qoif";
+
+/// Largest image we're willing to decode, to avoid running out of memory on a
+/// corrupt header
+const MAX_PIXELS: u64 = 4_000_000;
+
+const OP_RGB: u8 = 0xFE;
+const OP_RGBA: u8 = 0xFF;
+const OP_INDEX: u8 = 0b00;
+const OP_DIFF: u8 = 0b01;
+const OP_LUMA: u8 = 0b10;
+const OP_RUN: u8 = 0b11;
+
+/// Decodes a QOI image, ignoring its alpha channel.
+pub fn decode(data: &[u8]) -> Result<Pixels, &'static str> {
+    if data.len() < 14 || &data[0..4] != MAGIC {
+        return Err("missing QOI header");
+    }
+    let width = u32::from_be_bytes(data[4..8].try_into().unwrap());
+    let height = u32::from_be_bytes(data[8..12].try_into().unwrap());
+    let len = width as u64 * height as u64;
+    if len == 0 || len > MAX_PIXELS {
+        return Err("image size is zero or too large");
+    }
+
+    let mut bytes = data[14..].iter().copied();
+    let mut next = || bytes.next().ok_or("image data ends early");
+
+    let mut index = [[0u8; 4]; 64];
+    let mut px = [0u8, 0, 0, 255];
+    let mut run = 0;
+    let mut pixels = Vec::with_capacity(len as usize);
+    for _ in 0..len {
+        if run > 0 {
+            run -= 1;
+        } else {
+            let b1 = next()?;
+            match b1 {
+                OP_RGB => px = [next()?, next()?, next()?, px[3]],
+                OP_RGBA => px = [next()?, next()?, next()?, next()?],
+                _ => match b1 >> 6 {
+                    OP_INDEX => px = index[(b1 & 0x3F) as usize],
+                    OP_DIFF => {
+                        px[0] = px[0].wrapping_add((b1 >> 4) & 0x03).wrapping_sub(2);
+                        px[1] = px[1].wrapping_add((b1 >> 2) & 0x03).wrapping_sub(2);
+                        px[2] = px[2].wrapping_add(b1 & 0x03).wrapping_sub(2);
+                    }
+                    OP_LUMA => {
+                        let b2 = next()?;
+                        let dg = (b1 & 0x3F).wrapping_sub(32);
+                        px[0] = px[0].wrapping_add(dg.wrapping_add(b2 >> 4).wrapping_sub(8));
+                        px[1] = px[1].wrapping_add(dg);
+                        px[2] = px[2].wrapping_add(dg.wrapping_add(b2 & 0x0F).wrapping_sub(8));
+                    }
+                    OP_RUN => run = b1 & 0x3F,
+                    _ => unreachable!(),
+                },
+            }
+            let hash = (px[0] as usize * 3
+                + px[1] as usize * 5
+                + px[2] as usize * 7
+                + px[3] as usize * 11)
+                % 64;
+            index[hash] = px;
+        }
+        pixels.push(Color::new(px[0], px[1], px[2]));
+    }
+
+    Ok(Pixels {
+        width,
+        height,
+        data: pixels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::prelude::{OriginDimensions, Size};
+
+    use super::*;
+
+    /// Wraps QOI chunks in a header and end marker.
+    fn image(width: u32, height: u32, chunks: &[u8]) -> Vec<u8> {
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&width.to_be_bytes());
+        data.extend_from_slice(&height.to_be_bytes());
+        data.extend_from_slice(&[4, 0]);
+        data.extend_from_slice(chunks);
+        data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]);
+        data
+    }
+
+    #[test]
+    fn decodes_every_op() {
+        let chunks: [&[u8]; 6] = [
+            // RGBA
+            &[0xFF, 0x10, 0x20, 0x30, 0xFF],
+            // DIFF of +1, -1, 0
+            &[0x76],
+            // LUMA with a green difference of +5, and red +2 and blue -3
+            // relative to it
+            &[0xA5, 0xA5],
+            // RUN of 2
+            &[0xC1],
+            // INDEX of the first pixel
+            &[0x15],
+            // RGB
+            &[0xFE, 0x00, 0x00, 0xFF],
+        ];
+        let data = image(7, 1, &chunks.concat());
+        let pixels = decode(&data).unwrap();
+        assert_eq!(pixels.size(), Size::new(7, 1));
+        assert_eq!(
+            pixels.data,
+            [
+                Color::new(16, 32, 48),
+                Color::new(17, 31, 48),
+                Color::new(24, 36, 50),
+                Color::new(24, 36, 50),
+                Color::new(24, 36, 50),
+                Color::new(16, 32, 48),
+                Color::new(0, 0, 255),
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_default_logo() {
+        let pixels = decode(include_bytes!("../../../assets/logo-cropped.qoi")).unwrap();
+        assert_eq!(pixels.size(), Size::new(200, 179));
+        assert_eq!(pixels.get(0, 0), Color::new(18, 19, 24));
+        assert_eq!(pixels.get(100, 89), Color::new(29, 30, 35));
+        assert_eq!(pixels.get(199, 178), Color::new(75, 75, 79));
+    }
+
+    #[test]
+    fn rejects_bad_images() {
+        assert!(decode(b"qoif").is_err());
+        assert!(decode(&image(0, 1, &[])).is_err());
+        // Two pixels are promised but only one is there
+        assert!(decode(&image(2, 1, &[0xFE, 0, 0, 0])[..18]).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use embedded_graphics::{
+    prelude::*,
+    primitives::{Circle, Line, Polyline, PrimitiveStyle},
+};
+use unwrap_infallible::UnwrapInfallible;
+
+use super::image::Pixels;
+use crate::{route::RoutePath, theme::ColorScheme, Pose};
+
+/// Width and height of the path preview in pixels
+pub const SIZE: u32 = 112;
+
+/// Width and height of a VEX field in inches
+const FIELD_SIZE: f32 = 144.0;
+
+/// Number of tiles along each side of the field
+const TILES: u32 = 6;
+
+/// Speed of the robot marker along the path, in pixels per second
+const MARKER_SPEED: f32 = 60.0;
+
+/// How long the robot marker waits at the start and end of the path
+const MARKER_PAUSE: Duration = Duration::from_millis(800);
+
+/// Route path drawn over the field, with the robot marker's stops.
+#[derive(Debug)]
+pub struct PathPreview {
+    /// Owned rather than leaked, since previews are redrawn whenever the
+    /// theme changes
+    pub image: Pixels,
+    /// Start followed by each waypoint, in pixels from the top left
+    pub points: Vec<Point>,
+}
+
+impl PathPreview {
+    /// Draws the path over the field image, or a plain tiled field if there's
+    /// no image.
+    pub fn new(path: &RoutePath, field: Option<&Pixels>, scheme: &ColorScheme) -> Self {
+        let mut pixels = match field {
+            Some(field) => field.resize(SIZE, SIZE),
+            None => {
+                let mut pixels = Pixels::new(SIZE, SIZE, scheme.surface_container_high);
+                let grid = PrimitiveStyle::with_stroke(scheme.outline_variant, 1);
+                for i in 1..TILES {
+                    let offset = (i * SIZE / TILES) as i32;
+                    Line::new(Point::new(offset, 0), Point::new(offset, SIZE as i32))
+                        .into_styled(grid)
+                        .draw(&mut pixels)
+                        .unwrap_infallible();
+                    Line::new(Point::new(0, offset), Point::new(SIZE as i32, offset))
+                        .into_styled(grid)
+                        .draw(&mut pixels)
+                        .unwrap_infallible();
+                }
+                pixels
+            }
+        };
+
+        let points = std::iter::once(&path.start)
+            .chain(&path.waypoints)
+            .map(to_pixels)
+            .collect::<Vec<_>>();
+
+        Polyline::new(&points)
+            .into_styled(PrimitiveStyle::with_stroke(scheme.primary, 2))
+            .draw(&mut pixels)
+            .unwrap_infallible();
+
+        // Starting position, with a line showing which way the robot faces
+        let start = points[0];
+        let heading = path.start.heading.to_radians();
+        let facing = start
+            + Point::new(
+                (heading.sin() * 10.0) as i32,
+                -(heading.cos() * 10.0) as i32,
+            );
+        Line::new(start, facing)
+            .into_styled(PrimitiveStyle::with_stroke(scheme.tertiary, 2))
+            .draw(&mut pixels)
+            .unwrap_infallible();
+        Circle::with_center(start, 9)
+            .into_styled(PrimitiveStyle::with_fill(scheme.tertiary))
+            .draw(&mut pixels)
+            .unwrap_infallible();
+
+        Self {
+            image: pixels,
+            points,
+        }
+    }
+
+    /// How long the robot marker takes to move to the given stop.
+    pub fn travel_duration(&self, step: usize) -> Duration {
+        if step == 0 {
+            // The marker jumps back to the start
+            return Duration::ZERO;
+        }
+        let delta = self.points[step] - self.points[step - 1];
+        let distance = ((delta.x * delta.x + delta.y * delta.y) as f32).sqrt();
+        Duration::from_secs_f32(distance / MARKER_SPEED)
+    }
+
+    /// How long the robot marker spends getting to and waiting at the given
+    /// stop before moving on.
+    pub fn step_duration(&self, step: usize) -> Duration {
+        let travel = self.travel_duration(step);
+        if step == 0 || step == self.points.len() - 1 {
+            travel + MARKER_PAUSE
+        } else {
+            travel
+        }
+    }
+}
+
+/// Converts a field position to pixels in the preview.
+fn to_pixels(pose: &Pose) -> Point {
+    let scale = SIZE as f32 / FIELD_SIZE;
+    Point::new(
+        ((pose.x + FIELD_SIZE / 2.0) * scale) as i32,
+        ((FIELD_SIZE / 2.0 - pose.y) * scale) as i32,
+    )
+}
@@ -0,0 +1,129 @@
+use std::{cell::RefCell, rc::Rc};
+
+use vexide::competition::CompetitionMode;
+
+use super::{
+    framebuffer::Framebuffer,
+    ui::{AppData, AppState, Screen},
+};
+use crate::{
+    branding::Branding, builder::Config, theme::Theme, typography::Typography, Category,
+    DoxaSelectInterface, ExternalState, Route,
+};
+
+/// Screen shown by a [`Preview`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewScreen {
+    /// The category grid
+    SelectCategory,
+    /// The route grid for the category at the given index, in sorted order
+    SelectRoute(usize),
+    /// The confirmation screen for the route at the given index
+    ConfirmSelection(usize),
+    /// The confirmed screen for the route at the given index
+    Confirmed(usize),
+    /// The diagnostics screen, opened from the category grid
+    Diagnostics,
+}
+
+/// Off-screen renderer for previewing the selector's UI without a brain.
+///
+/// This renders the same views as [`DoxaSelect`] into a [`Framebuffer`], which
+/// can then be saved as an image. It's intended for iterating on screens on a
+/// host machine.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut preview = Preview::new(&routes);
+/// preview.show(PreviewScreen::ConfirmSelection(0));
+/// std::fs::write("confirm.png", preview.render().to_png()).unwrap();
+/// ```
+///
+/// [`DoxaSelect`]: crate::DoxaSelect
+pub struct Preview {
+    app_state: AppState,
+    app_data: AppData,
+}
+
+impl Preview {
+    /// Creates a preview of the selector for the given routes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `routes` is empty.
+    pub fn new<C: Category, R: 'static>(routes: &[Route<C, R>]) -> Self {
+        assert!(!routes.is_empty(), "Preview requires at least one route.");
+
+        let categories = crate::route::categories(routes);
+        Self {
+            app_state: AppState::new(
+                Rc::new(RefCell::new(ExternalState::new(0))),
+                Box::new(crate::builder::NoInterface),
+                CompetitionMode::Disabled,
+            ),
+            app_data: AppData::new(routes.to_vec(), categories, Config::default()),
+        }
+    }
+
+    /// Sets the interface used for calibration and diagnostics.
+    pub fn interface(mut self, interface: impl DoxaSelectInterface + 'static) -> Self {
+        self.app_state.interface = Box::new(interface);
+        self.app_state.refresh_actions();
+        self.app_state.refresh_checklist(&self.app_data);
+        self
+    }
+
+    /// Sets the colors of the UI.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.app_data.set_theme(theme);
+        self
+    }
+
+    /// Sets the team identity and images shown in the UI.
+    pub fn branding(mut self, branding: Branding) -> Self {
+        self.app_data.set_branding(branding);
+        self
+    }
+
+    /// Sets the fonts and text sizes of the UI.
+    pub fn typography(mut self, typography: Typography) -> Self {
+        self.app_data.set_typography(typography);
+        self
+    }
+
+    /// Switches to the given screen.
+    pub fn show(&mut self, screen: PreviewScreen) {
+        {
+            let mut external = self.app_state.external.borrow_mut();
+            if let PreviewScreen::ConfirmSelection(route) | PreviewScreen::Confirmed(route) = screen
+            {
+                external.selection = route;
+            }
+            external.confirmed = matches!(screen, PreviewScreen::Confirmed(_));
+        }
+
+        self.app_state.screen = match screen {
+            PreviewScreen::SelectCategory => Screen::SelectCategory,
+            PreviewScreen::SelectRoute(category) => Screen::SelectRoute(category),
+            PreviewScreen::ConfirmSelection(_) => Screen::ConfirmSelection,
+            PreviewScreen::Confirmed(_) => Screen::Confirmed,
+            PreviewScreen::Diagnostics => {
+                self.app_state.refresh_diagnostics();
+                Screen::Diagnostics(Box::new(Screen::SelectCategory))
+            }
+        };
+    }
+
+    /// Sets the competition mode the UI behaves as if it were in.
+    pub fn set_competition_mode(&mut self, mode: CompetitionMode) {
+        self.app_state.mode = mode;
+    }
+
+    /// Renders the current screen, with all animations finished.
+    pub fn render(&mut self) -> Framebuffer {
+        let mut framebuffer = Framebuffer::new();
+        super::render_frame(&self.app_data, &mut self.app_state, &mut framebuffer);
+        framebuffer
+    }
+}
@@ -1,22 +1,56 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc, time::Duration};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 use buoyant::{
     transition::{Move, Slide},
     view::prelude::*,
 };
+use embedded_graphics::image::ImageRaw;
+use vexide::competition::CompetitionMode;
 
-use crate::{view::image, ExternalState, Route};
+use crate::{
+    branding::{Branding, LogoPosition},
+    builder::Config,
+    diagnostics::{DiagnosticHistory, DiagnosticKey},
+    driver::ControllerAction,
+    persist,
+    route::{Alliance, FieldSide, RoutePath},
+    theme::{ColorScheme, Theme},
+    typography::Typography,
+    view::{color::Color, font::Fonts, image, path::PathPreview},
+    ActionStyle, CalibrationResult, ChecklistItem, ConfirmError, DiagnosticEntry, ExternalState,
+    QuickAction, Route, RouteInfo, ScreenRequest,
+};
 
+mod actions_menu;
 mod bottom_bar;
 mod button;
 mod calibrating_overlay;
+mod calibration_result_overlay;
 mod card;
 mod confirm_selection_screen;
+mod confirmed_screen;
 mod diagnostics_screen;
 mod select_category_screen;
 mod select_route_screen;
 mod selector;
 
+use bottom_bar::BarAction;
+use selector::GridLayout;
+
+/// Number of recently confirmed routes that are remembered
+const MAX_RECENTS: usize = 3;
+
+/// Number of shortcuts that fit at the top of the category screen
+const MAX_SHORTCUTS: usize = 4;
+
+/// Height taken from the category grid by the row of shortcuts
+const SHORTCUTS_HEIGHT: u32 = 44;
+
 /// Application data shared across views.
 ///
 /// Will not change after initialization and is intended to be created once and
@@ -31,13 +65,34 @@ pub(super) struct AppData {
     category_names: Vec<(usize, String, usize)>,
     /// (category_index) -> Vec<(route_index, route_name, global_route_index)>
     route_names_map: HashMap<usize, Vec<(usize, String, usize)>>,
-    routes: Vec<(&'static str, &'static str)>,
+    /// (route_index) -> route details
+    routes: Vec<RouteData>,
+    config: Config,
+    fonts: Fonts,
+    /// Team identity shown in the bottom bar
+    footer: String,
+    logo: Option<ImageRaw<'static, Color>>,
+    selected_background: Option<ImageRaw<'static, Color>>,
+}
+
+/// Details of a single route needed by the UI.
+#[derive(Debug)]
+struct RouteData {
+    name: &'static str,
+    description: &'static str,
+    category_index: usize,
+    time_budget: Duration,
+    path: Option<RoutePath>,
+    path_preview: Option<PathPreview>,
+    alliance: Option<Alliance>,
+    side: Option<FieldSide>,
 }
 
 impl AppData {
     pub fn new<C: crate::route::Category, R: 'static>(
         routes: Vec<Route<C, R>>,
         categories: Vec<C>,
+        config: Config,
     ) -> Self {
         let category_names = categories
             .iter()
@@ -45,6 +100,7 @@ impl AppData {
             .map(|(i, c)| (i, c.to_string(), i))
             .collect();
         let mut route_names_map: HashMap<usize, Vec<(usize, String, usize)>> = HashMap::new();
+        let mut route_entries = Vec::with_capacity(routes.len());
         for (i, route) in routes.iter().enumerate() {
             let category_index = categories
                 .iter()
@@ -52,11 +108,101 @@ impl AppData {
                 .expect("Route category not found in categories list.");
             let entry = route_names_map.entry(category_index).or_default();
             entry.push((entry.len(), route.name.to_string(), i));
+            route_entries.push(RouteData {
+                name: route.name,
+                description: route.description,
+                category_index,
+                time_budget: route.time_budget,
+                path: route.path.clone(),
+                path_preview: None,
+                alliance: route.alliance,
+                side: route.side,
+            });
         }
-        Self {
+        let mut data = Self {
             category_names,
             route_names_map,
-            routes: routes.iter().map(|r| (r.name, r.description)).collect(),
+            routes: route_entries,
+            fonts: Fonts::new(&config.typography),
+            footer: config.branding.footer(),
+            logo: image::load(&config.branding.logo, config.branding.logo_fit),
+            selected_background: image::load(
+                &config.branding.selected_background,
+                config.branding.background_fit,
+            ),
+            config,
+        };
+        data.render_paths();
+        data
+    }
+
+    /// Draws the path previews of routes with paths in the current theme.
+    fn render_paths(&mut self) {
+        if self.routes.iter().all(|route| route.path.is_none()) {
+            return;
+        }
+        let field = image::read(&self.config.field_image);
+        let scheme = *self.scheme();
+        for route in &mut self.routes {
+            route.path_preview = route
+                .path
+                .as_ref()
+                .map(|path| PathPreview::new(path, field.as_ref(), &scheme));
+        }
+    }
+
+    /// Replaces the theme, redrawing the path previews in its colors.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.config.theme = theme;
+        self.render_paths();
+    }
+
+    /// Replaces the branding, reloading its images.
+    pub fn set_branding(&mut self, branding: Branding) {
+        self.footer = branding.footer();
+        self.logo = image::load(&branding.logo, branding.logo_fit);
+        self.selected_background =
+            image::load(&branding.selected_background, branding.background_fit);
+        self.config.branding = branding;
+    }
+
+    /// Replaces the typography, reloading its fonts.
+    pub fn set_typography(&mut self, typography: Typography) {
+        self.fonts = Fonts::new(&typography);
+        self.config.typography = typography;
+    }
+
+    /// Returns the color scheme currently in use.
+    pub fn scheme(&self) -> &ColorScheme {
+        self.config.theme.scheme()
+    }
+
+    /// Returns the fonts currently in use.
+    pub fn fonts(&self) -> &Fonts {
+        &self.fonts
+    }
+
+    /// Returns the `(category, name)` key a route is saved under.
+    fn saved_route(&self, index: usize) -> (&str, &'static str) {
+        let route = &self.routes[index];
+        (&self.category_names[route.category_index].1, route.name)
+    }
+
+    /// Finds the route saved under the given key, if it still exists.
+    fn find_saved_route(&self, saved: &persist::SavedRoute) -> Option<usize> {
+        (0..self.routes.len()).find(|&index| {
+            self.saved_route(index) == (saved.category.as_str(), saved.name.as_str())
+        })
+    }
+
+    /// Builds the [`RouteInfo`] passed to interface hooks.
+    fn route_info(&self, index: usize) -> RouteInfo {
+        let route = &self.routes[index];
+        RouteInfo {
+            index,
+            category: self.category_names[route.category_index].1.clone(),
+            name: route.name,
+            description: route.description,
         }
     }
 }
@@ -65,7 +211,54 @@ pub(super) struct AppState {
     /// Current screen
     pub screen: Screen,
     /// Cached diagnostics data
-    diagnostics: Option<Vec<(String, String)>>,
+    diagnostics: Option<Vec<DiagnosticEntry>>,
+    /// When the diagnostics were last polled from the interface
+    diagnostics_polled_at: Instant,
+    /// Recent values of the numeric diagnostics
+    diagnostic_history: DiagnosticHistory,
+    /// Diagnostic entry whose history is shown full-screen
+    chart: Option<DiagnosticKey>,
+    /// Competition mode as of the current frame
+    pub mode: CompetitionMode,
+    /// Index of the item with controller focus on the current screen
+    ///
+    /// `None` until the controller is used, so touch-only users never see a
+    /// focus highlight.
+    pub focus: Option<usize>,
+    /// Stop of the path preview the robot marker is moving to or waiting at
+    path_step: usize,
+    /// When the robot marker started moving to the current stop
+    path_step_at: Instant,
+    /// Page of the category or route grid that is shown
+    page: usize,
+    /// Routes pinned to the top of the category screen
+    favorites: Vec<usize>,
+    /// Recently confirmed routes, most recent first
+    recents: Vec<usize>,
+    /// Whether the outcome of the last calibration is shown over the UI
+    pub calibration_alert: bool,
+    /// When the selection was confirmed, unless it was restored at startup
+    confirmed_at: Option<Instant>,
+    /// Time since confirmation as shown on the confirmed screen
+    confirmed_clock: String,
+    /// Pre-match checklist from the builder and interface as of the current
+    /// frame
+    checklist: Vec<ChecklistItem>,
+    /// Labels of the checklist items ticked by hand, so ticks stay with their
+    /// item when others are added or removed
+    ticked: HashSet<String>,
+    /// Page of the checklist that is shown
+    checklist_page: usize,
+    /// Quick actions from the interface as of the current frame
+    actions: Vec<QuickAction>,
+    /// Whether the menu of quick actions that don't fit in the bottom bar is
+    /// open
+    actions_menu: bool,
+    /// Page of the actions menu that is shown
+    actions_page: usize,
+    /// Index of the button with controller focus in the actions menu or
+    /// calibration alert, whichever is open
+    menu_focus: usize,
 
     /// External state shared with the main DoxaSelect struct
     pub external: Rc<RefCell<ExternalState>>,
@@ -76,20 +269,856 @@ pub(super) struct AppState {
 impl AppState {
     pub fn new(
         external: Rc<RefCell<ExternalState>>,
-        interface: impl crate::DoxaSelectInterface + 'static,
+        interface: Box<dyn crate::DoxaSelectInterface>,
+        mode: CompetitionMode,
     ) -> Self {
         Self {
             screen: Screen::default(),
             external,
-            interface: Box::new(interface),
+            interface,
             diagnostics: None,
+            diagnostics_polled_at: Instant::now(),
+            diagnostic_history: DiagnosticHistory::default(),
+            chart: None,
+            mode,
+            focus: None,
+            path_step: 0,
+            path_step_at: Instant::now(),
+            page: 0,
+            favorites: Vec::new(),
+            recents: Vec::new(),
+            calibration_alert: false,
+            confirmed_at: None,
+            confirmed_clock: String::new(),
+            checklist: Vec::new(),
+            ticked: HashSet::new(),
+            checklist_page: 0,
+            actions: Vec::new(),
+            actions_menu: false,
+            actions_page: 0,
+            menu_focus: 0,
+        }
+    }
+
+    /// Restores the favorite and recent routes from the SD card.
+    pub fn load_shortcuts(&mut self, data: &AppData) {
+        self.set_shortcuts(&persist::load_shortcuts(), data);
+    }
+
+    /// Looks up saved favorite and recent routes by name, dropping any that no
+    /// longer exist.
+    fn set_shortcuts(&mut self, saved: &persist::SavedShortcuts, data: &AppData) {
+        let find = |routes: &[persist::SavedRoute]| {
+            routes
+                .iter()
+                .filter_map(|route| data.find_saved_route(route))
+                .collect::<Vec<_>>()
+        };
+        self.favorites = find(&saved.favorites);
+        self.recents = find(&saved.recents);
+        self.recents.truncate(MAX_RECENTS);
+    }
+
+    fn save_shortcuts(&self, data: &AppData) {
+        let saved = |routes: &[usize]| {
+            routes
+                .iter()
+                .map(|&index| data.saved_route(index))
+                .collect::<Vec<_>>()
+        };
+        persist::save_shortcuts(&saved(&self.favorites), &saved(&self.recents));
+    }
+
+    /// Returns the routes shown at the top of the category screen: favorites
+    /// first, then recent routes that aren't favorites.
+    fn shortcuts(&self) -> Vec<usize> {
+        let mut shortcuts = self.favorites.clone();
+        for &route in &self.recents {
+            if !shortcuts.contains(&route) {
+                shortcuts.push(route);
+            }
+        }
+        shortcuts.truncate(MAX_SHORTCUTS);
+        shortcuts
+    }
+
+    /// Moves a route to the front of the recent routes.
+    fn push_recent(&mut self, route: usize) {
+        self.recents.retain(|&recent| recent != route);
+        self.recents.insert(0, route);
+        self.recents.truncate(MAX_RECENTS);
+    }
+
+    /// Whether the selected route is pinned as a favorite.
+    fn is_favorite(&self) -> bool {
+        self.favorites.contains(&self.external.borrow().selection)
+    }
+
+    /// Pins or unpins the selected route.
+    fn toggle_favorite(&mut self, data: &AppData) {
+        let selection = self.external.borrow().selection;
+        if let Some(position) = self.favorites.iter().position(|&i| i == selection) {
+            self.favorites.remove(position);
+        } else {
+            self.favorites.push(selection);
+        }
+        self.save_shortcuts(data);
+    }
+
+    /// Returns the items and layout of the grid on the current screen, if it
+    /// has one.
+    fn grid<'a>(&self, data: &'a AppData) -> Option<(&'a [(usize, String, usize)], GridLayout)> {
+        let (items, height) = match self.screen {
+            Screen::SelectCategory => (
+                &data.category_names[..],
+                if self.shortcuts().is_empty() {
+                    selector::GRID_HEIGHT
+                } else {
+                    selector::GRID_HEIGHT - SHORTCUTS_HEIGHT
+                },
+            ),
+            Screen::SelectRoute(category_index) => (
+                &data.route_names_map[&category_index][..],
+                selector::GRID_HEIGHT,
+            ),
+            _ => return None,
+        };
+        let layout = GridLayout::new(
+            items,
+            data.config.grid_columns,
+            data.config.card_height,
+            height,
+            &data.fonts,
+        );
+        Some((items, layout))
+    }
+
+    /// Shows a different page of the grid, moving the controller focus onto it.
+    fn set_page(&mut self, page: usize, layout: GridLayout) {
+        self.page = page;
+        if self.focus.is_some() {
+            self.focus = Some(page * layout.page_size());
+        }
+    }
+
+    /// Whether the diagnostics are being kept up to date.
+    ///
+    /// Live diagnostics pause during autonomous so they don't take time from
+    /// the route.
+    pub fn diagnostics_live(&self, data: &AppData) -> bool {
+        data.config.diagnostics_interval.is_some()
+            && matches!(
+                self.mode,
+                CompetitionMode::Disabled | CompetitionMode::Driver
+            )
+    }
+
+    /// Re-polls the diagnostics if live diagnostics are on and the interval has
+    /// passed.
+    ///
+    /// Polling carries on while other screens are open so that the history
+    /// has no gaps when the diagnostics screen is opened again.
+    ///
+    /// Returns whether the diagnostics screen needs to be rebuilt, which is
+    /// when a row it shows changed or has a sparkline or chart that gained a
+    /// sample. Rebuilding redraws the whole view, so nothing is rebuilt for
+    /// changes to rows that aren't shown.
+    pub fn poll_diagnostics(&mut self, data: &AppData) -> bool {
+        let Some(interval) = data.config.diagnostics_interval else {
+            return false;
+        };
+        if !self.diagnostics_live(data) || self.diagnostics_polled_at.elapsed() < interval {
+            return false;
+        }
+
+        let previous = self.visible_diagnostics();
+        self.refresh_diagnostics();
+        let visible = self.visible_diagnostics();
+        visible != previous
+            || visible.iter().any(|row| match row {
+                diagnostics_screen::Row::Entry(entry) => {
+                    self.diagnostic_history.get(entry).is_some()
+                }
+                diagnostics_screen::Row::Section(_) => false,
+            })
+    }
+
+    /// Rows of the diagnostics screen that are currently shown, or none if
+    /// it isn't open.
+    ///
+    /// The rows leave out the history, which [`poll_diagnostics`] checks
+    /// separately.
+    ///
+    /// [`poll_diagnostics`]: Self::poll_diagnostics
+    fn visible_diagnostics(&self) -> Vec<diagnostics_screen::Row> {
+        if !matches!(self.screen, Screen::Diagnostics(_)) {
+            return Vec::new();
+        }
+        let entries = self.diagnostics.as_deref().unwrap_or_default();
+        match &self.chart {
+            Some(key) => diagnostics_screen::chart(entries, key)
+                .into_iter()
+                .collect(),
+            None => {
+                diagnostics_screen::page(entries, self.interface.diagnostics_compact(), self.page)
+            }
+        }
+    }
+
+    /// Shows the history of a diagnostic entry full-screen.
+    fn open_chart(&mut self, key: DiagnosticKey) {
+        self.chart = Some(key);
+    }
+
+    /// Returns from an entry's chart to the list of diagnostics.
+    fn close_chart(&mut self) {
+        self.chart = None;
+    }
+
+    /// Moves the path preview's robot marker on to the next stop once it's done
+    /// with the current one.
+    ///
+    /// Returns whether the marker moved, in which case the view needs to be
+    /// rebuilt.
+    pub fn advance_path_preview(&mut self, data: &AppData) -> bool {
+        if !matches!(self.screen, Screen::ConfirmSelection) {
+            return false;
+        }
+        let selection = self.external.borrow().selection;
+        let Some(preview) = &data.routes[selection].path_preview else {
+            return false;
+        };
+        if preview.points.len() < 2
+            || self.path_step_at.elapsed() < preview.step_duration(self.path_step)
+        {
+            return false;
+        }
+        self.path_step = (self.path_step + 1) % preview.points.len();
+        self.path_step_at = Instant::now();
+        true
+    }
+
+    /// Switches to a different screen.
+    fn navigate(&mut self, screen: Screen) {
+        self.screen = screen;
+        self.page = 0;
+        self.chart = None;
+        // Start the path preview over on the confirmation screen
+        self.path_step = 0;
+        self.path_step_at = Instant::now();
+        // Items on the new screen are unrelated to the old focus
+        if self.focus.is_some() {
+            self.focus = Some(0);
+        }
+    }
+
+    /// Fetches the quick actions from the interface.
+    ///
+    /// Returns whether they changed, in which case the view needs to be
+    /// rebuilt.
+    pub fn refresh_actions(&mut self) -> bool {
+        let actions = self.interface.actions();
+        if actions == self.actions {
+            return false;
+        }
+        self.actions = actions;
+        let len = self.bar_actions().len();
+        self.actions_page = self.actions_page.min(actions_menu::page_count(len) - 1);
+        self.menu_focus = self.menu_focus.min(len.saturating_sub(1));
+        true
+    }
+
+    /// Actions of the bottom bar and actions menu: the interface's quick
+    /// actions, followed by calibrating and diagnostics if they're enabled.
+    fn bar_actions(&self) -> Vec<(BarAction, QuickAction)> {
+        let mut actions = self
+            .actions
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(index, action)| (BarAction::Quick(index), action))
+            .collect::<Vec<_>>();
+        if self.interface.calibrating_enable() {
+            actions.push((BarAction::Calibrate, QuickAction::new("Calibrate")));
+        }
+        if self.interface.diagnostics_enable() {
+            actions.push(match self.screen {
+                Screen::Diagnostics(_) => (
+                    BarAction::Diagnostics,
+                    QuickAction::new("Exit diagnostics").style(ActionStyle::Primary),
+                ),
+                _ => (BarAction::Diagnostics, QuickAction::new("Diagnostics")),
+            });
+        }
+        actions
+    }
+
+    /// Buttons shown in the bottom bar, in focus order.
+    ///
+    /// If the actions don't all fit, a single button opens the menu of all of
+    /// them instead.
+    fn bar_buttons(&self) -> Vec<(BarAction, QuickAction)> {
+        let actions = self.bar_actions();
+        if actions.len() <= actions_menu::MAX_BAR_ACTIONS {
+            actions
+        } else {
+            vec![(BarAction::Menu, QuickAction::new("Actions"))]
+        }
+    }
+
+    /// Runs a button of the bottom bar or actions menu, closing the menu.
+    fn run_bar_action(&mut self, action: BarAction) {
+        match action {
+            BarAction::Quick(index) => self.run_action(index),
+            BarAction::Calibrate => {
+                self.actions_menu = false;
+                self.interface.calibrating_calibrate();
+            }
+            BarAction::Diagnostics => {
+                self.actions_menu = false;
+                self.toggle_diagnostics();
+            }
+            BarAction::Menu => {
+                self.actions_menu = true;
+                self.actions_page = 0;
+                self.menu_focus = 0;
+            }
+        }
+    }
+
+    /// Runs a quick action if it's enabled, closing the actions menu.
+    fn run_action(&mut self, index: usize) {
+        if self.actions.get(index).is_some_and(|action| action.enabled) {
+            self.actions_menu = false;
+            self.interface.on_action(index);
+        }
+    }
+
+    /// Updates the time since confirmation shown on the confirmed screen.
+    ///
+    /// Returns whether the shown time changed, in which case the view needs to
+    /// be rebuilt. It's shown to the second for the first minute, then to the
+    /// minute so the display can go idle in between.
+    pub fn tick_confirmed_clock(&mut self) -> bool {
+        if !matches!(self.screen, Screen::Confirmed) || self.mode == CompetitionMode::Autonomous {
+            return false;
+        }
+        let Some(confirmed_at) = self.confirmed_at else {
+            return false;
+        };
+        let elapsed = confirmed_at.elapsed().as_secs();
+        let clock = if elapsed < 60 {
+            format!("Confirmed {elapsed} s ago")
+        } else {
+            format!("Confirmed {} min ago", elapsed / 60)
+        };
+        if clock == self.confirmed_clock {
+            return false;
+        }
+        self.confirmed_clock = clock;
+        true
+    }
+
+    /// Label, background and text colors describing the calibration, or `None`
+    /// if calibration is disabled.
+    fn calibration_chip(&self, scheme: &ColorScheme) -> Option<(&'static str, Color, Color)> {
+        if !self.interface.calibrating_enable() {
+            return None;
         }
+        Some(match &self.external.borrow().calibration {
+            Some(CalibrationResult::Success) => (
+                "Calibrated",
+                scheme.secondary_container,
+                scheme.on_secondary_container,
+            ),
+            Some(CalibrationResult::Warning(_)) => (
+                "Calibration warning",
+                scheme.tertiary_container,
+                scheme.on_tertiary_container,
+            ),
+            Some(CalibrationResult::Failed(_)) => (
+                "Calibration failed",
+                scheme.error_container,
+                scheme.on_error_container,
+            ),
+            None => (
+                "Not calibrated",
+                scheme.error_container,
+                scheme.on_error_container,
+            ),
+        })
     }
 
-    fn refresh_diagnostics(&mut self) {
+    /// Whether the confirmation screen should flag the calibration, because
+    /// the robot hasn't been calibrated or its calibration had problems.
+    fn calibration_flagged(&self) -> bool {
+        self.interface.calibrating_enable()
+            && self.external.borrow().calibration != Some(CalibrationResult::Success)
+    }
+
+    /// Fetches the pre-match checklist from the builder and interface.
+    ///
+    /// Returns whether it changed, in which case the view needs to be rebuilt.
+    pub fn refresh_checklist(&mut self, data: &AppData) -> bool {
+        let mut checklist = data.config.checklist.clone();
+        checklist.extend(self.interface.checklist());
+        if checklist == self.checklist {
+            return false;
+        }
+        // Forget the ticks of items that are gone, so an item that comes back
+        // later starts unticked
+        self.ticked
+            .retain(|label| checklist.iter().any(|item| item.label == *label));
+        self.checklist_page = self
+            .checklist_page
+            .min(confirm_selection_screen::checklist_page_count(checklist.len()) - 1);
+        self.checklist = checklist;
+        true
+    }
+
+    /// Whether the checklist item at `index` is done, either ticked by hand or
+    /// checked by the robot.
+    fn is_checked(&self, index: usize) -> bool {
+        self.checklist.get(index).is_some_and(|item| {
+            item.done
+                .unwrap_or_else(|| self.ticked.contains(&item.label))
+        })
+    }
+
+    /// Ticks or unticks a checklist item, unless the robot checks it.
+    fn toggle_check(&mut self, index: usize) {
+        let Some(item) = self.checklist.get(index) else {
+            return;
+        };
+        if item.done.is_none() && !self.ticked.remove(&item.label) {
+            self.ticked.insert(item.label.clone());
+        }
+    }
+
+    /// Clears the ticks so the checklist is fresh for the next match.
+    pub fn reset_checklist(&mut self) {
+        self.ticked.clear();
+        self.checklist_page = 0;
+    }
+
+    /// Number of required checklist items that aren't done yet.
+    fn checklist_remaining(&self) -> usize {
+        (0..self.checklist.len())
+            .filter(|&index| self.checklist[index].required && !self.is_checked(index))
+            .count()
+    }
+
+    /// Why confirming is blocked, or `None` if the route can be confirmed.
+    fn confirm_blocker(&self, data: &AppData) -> Option<ConfirmError> {
+        let calibrated = self
+            .external
+            .borrow()
+            .calibration
+            .as_ref()
+            .is_some_and(CalibrationResult::is_usable);
+        if data.config.require_calibration && self.interface.calibrating_enable() && !calibrated {
+            return Some(ConfirmError::NotCalibrated);
+        }
+        match self.checklist_remaining() {
+            0 => None,
+            remaining => Some(ConfirmError::ChecklistIncomplete { remaining }),
+        }
+    }
+
+    /// Shares why confirming is blocked with [`crate::DoxaSelect::confirm`],
+    /// which can't check the calibration and checklist itself.
+    pub fn publish_confirm_blocker(&self, data: &AppData) {
+        let blocker = self.confirm_blocker(data);
+        self.external.borrow_mut().confirm_blocker = blocker;
+    }
+
+    /// Starts calibrating again after a failure or warning.
+    fn retry_calibration(&mut self) {
+        self.dismiss_calibration_alert();
+        self.interface.calibrating_calibrate();
+    }
+
+    /// Hides the outcome of the last calibration.
+    fn dismiss_calibration_alert(&mut self) {
+        self.calibration_alert = false;
+        // The next alert starts on "Dismiss"
+        self.menu_focus = 0;
+    }
+
+    /// Selects a route and asks the user to confirm it.
+    fn select_route(&mut self, route_index: usize) {
+        self.external.borrow_mut().selection = route_index;
+        self.navigate(Screen::ConfirmSelection);
+    }
+
+    /// Confirms the selection restored from the SD card at startup and skips to
+    /// the confirmed screen.
+    ///
+    /// Calibration and the checklist don't survive a reboot, so if either
+    /// blocks confirming, the confirmation screen is shown instead for the
+    /// route to be confirmed by hand.
+    pub fn restore_selection(&mut self, data: &AppData) {
+        if !self.external.borrow().restored {
+            return;
+        }
+        self.refresh_checklist(data);
+        if self.confirm_blocker(data).is_some() {
+            self.navigate(Screen::ConfirmSelection);
+            return;
+        }
+        self.external.borrow_mut().confirmed = true;
+        self.navigate(Screen::Confirmed);
+    }
+
+    /// Confirms the current selection and saves it to the SD card.
+    ///
+    /// Does nothing if confirming is blocked by the calibration or checklist.
+    fn confirm(&mut self, data: &AppData) {
+        if self.confirm_blocker(data).is_some() {
+            return;
+        }
+        let selection = {
+            let mut external = self.external.borrow_mut();
+            external.confirmed = true;
+            external.restored = false;
+            external.selection
+        };
+        let (category, name) = data.saved_route(selection);
+        persist::save(category, name);
+        self.push_recent(selection);
+        self.save_shortcuts(data);
+        self.confirmed_at = Some(Instant::now());
+        self.confirmed_clock = String::from("Confirmed just now");
+        self.navigate(Screen::Confirmed);
+    }
+
+    /// Returns to category selection so a different route can be picked.
+    fn change_route(&mut self) {
+        {
+            let mut external = self.external.borrow_mut();
+            external.confirmed = false;
+            external.restored = false;
+        }
+        // A reboot mustn't bring back the route that was just unconfirmed
+        persist::clear();
+        self.confirmed_at = None;
+        self.navigate(Screen::SelectCategory);
+    }
+
+    /// Opens the diagnostics screen, or returns to the previous screen if it's
+    /// already open.
+    fn toggle_diagnostics(&mut self) {
+        let screen = match &self.screen {
+            // If already in diagnostics, go back to previous screen
+            Screen::Diagnostics(previous_screen) => *previous_screen.clone(),
+            // Otherwise, go to diagnostics, saving current screen
+            screen => {
+                let screen = screen.clone();
+                self.refresh_diagnostics();
+                Screen::Diagnostics(Box::new(screen))
+            }
+        };
+        self.navigate(screen);
+    }
+
+    /// Handles a screen change requested through the [`DoxaSelect`] API.
+    ///
+    /// [`DoxaSelect`]: crate::DoxaSelect
+    pub fn handle_request(&mut self, request: ScreenRequest, data: &AppData) {
+        match request {
+            ScreenRequest::ConfirmSelection => self.navigate(Screen::ConfirmSelection),
+            ScreenRequest::Confirm => self.confirm(data),
+        }
+    }
+
+    /// Number of items on the current screen that take controller focus, and
+    /// how many of them are in a row.
+    ///
+    /// The buttons of the bottom bar follow these items in focus order.
+    fn focus_items(&self, data: &AppData) -> (usize, usize) {
+        match self.grid(data) {
+            Some((items, layout)) => (items.len(), layout.columns),
+            // The checklist items, followed by the "Confirm" button
+            None if matches!(self.screen, Screen::ConfirmSelection) => {
+                (self.checklist.len() + 1, 1)
+            }
+            None => (0, 1),
+        }
+    }
+
+    /// Index of the bottom bar button with controller focus, if any.
+    fn bar_focus(&self, data: &AppData) -> Option<usize> {
+        let (item_count, _) = self.focus_items(data);
+        self.focus?.checked_sub(item_count)
+    }
+
+    /// Handles navigation input from the controller.
+    ///
+    /// This mirrors what tapping the equivalent on-screen controls does.
+    pub fn handle_controller(&mut self, action: ControllerAction, data: &AppData) {
+        // Overlays keep the controller until they're closed
+        if self.calibration_alert {
+            self.handle_alert_controller(action);
+            return;
+        }
+        if self.actions_menu {
+            self.handle_menu_controller(action);
+            return;
+        }
+
+        let grid = self.grid(data);
+        let (item_count, columns) = self.focus_items(data);
+        let bar = self.bar_buttons();
+
+        match action {
+            ControllerAction::Up
+            | ControllerAction::Down
+            | ControllerAction::Left
+            | ControllerAction::Right => {
+                // The diagnostics screen pages up and down instead of moving
+                // the focus, which only moves along the bottom bar
+                if let (Screen::Diagnostics(_), ControllerAction::Up | ControllerAction::Down) =
+                    (&self.screen, action)
+                {
+                    if self.chart.is_some() {
+                        return;
+                    }
+                    let page_count = diagnostics_screen::page_count(
+                        self.diagnostics.as_deref().unwrap_or_default(),
+                        self.interface.diagnostics_compact(),
+                    );
+                    self.page = match action {
+                        ControllerAction::Up => self.page.saturating_sub(1),
+                        _ => (self.page + 1).min(page_count - 1),
+                    };
+                    return;
+                }
+                // The first press only reveals the focus highlight
+                let Some(focus) = self.focus else {
+                    self.focus = Some(0);
+                    return;
+                };
+                let focus = if focus < item_count {
+                    match action {
+                        ControllerAction::Up => focus.saturating_sub(columns),
+                        // Moving down from the last row goes to the bottom bar
+                        ControllerAction::Down => (focus + columns).min(item_count),
+                        ControllerAction::Left => focus.saturating_sub(1),
+                        _ => focus + 1,
+                    }
+                } else {
+                    // The bottom bar is a single row after the items
+                    match action {
+                        ControllerAction::Up => item_count.checked_sub(1).unwrap_or(focus),
+                        ControllerAction::Down => focus,
+                        ControllerAction::Left => focus.saturating_sub(1),
+                        _ => focus + 1,
+                    }
+                };
+                let focus = focus.min((item_count + bar.len()).saturating_sub(1));
+                self.focus = Some(focus);
+                // Keep the focused item on screen
+                if let Some((_, layout)) = grid.filter(|_| focus < item_count) {
+                    self.page = layout.page_of(focus);
+                } else if focus < self.checklist.len() {
+                    self.checklist_page = confirm_selection_screen::checklist_page_of(focus);
+                }
+            }
+            ControllerAction::Select => {
+                let focus = self.focus.unwrap_or(0);
+                self.focus = Some(focus);
+                if focus >= item_count {
+                    if let Some(&(action, _)) = bar.get(focus - item_count) {
+                        self.run_bar_action(action);
+                    }
+                    return;
+                }
+                match self.screen {
+                    Screen::SelectCategory => self.navigate(Screen::SelectRoute(focus)),
+                    Screen::SelectRoute(category_index) => {
+                        self.select_route(data.route_names_map[&category_index][focus].2)
+                    }
+                    Screen::ConfirmSelection if focus < self.checklist.len() => {
+                        self.toggle_check(focus)
+                    }
+                    Screen::ConfirmSelection => self.confirm(data),
+                    _ => {}
+                }
+            }
+            ControllerAction::Back => {
+                self.focus = Some(self.focus.unwrap_or(0));
+                match self.screen {
+                    Screen::SelectRoute(_) | Screen::ConfirmSelection => {
+                        self.navigate(Screen::SelectCategory)
+                    }
+                    Screen::Confirmed => self.change_route(),
+                    Screen::Diagnostics(_) if self.chart.is_some() => self.close_chart(),
+                    Screen::Diagnostics(_) => self.toggle_diagnostics(),
+                    Screen::SelectCategory => {}
+                }
+            }
+        }
+    }
+
+    /// Handles controller input while the calibration alert is shown, with
+    /// "Dismiss" and "Retry" side by side.
+    fn handle_alert_controller(&mut self, action: ControllerAction) {
+        if self.focus.is_none() {
+            self.focus = Some(0);
+            // The first press only reveals the focus highlight, unless it's a
+            // button press
+            if !matches!(action, ControllerAction::Select | ControllerAction::Back) {
+                return;
+            }
+        }
+        match action {
+            ControllerAction::Up | ControllerAction::Left => self.menu_focus = 0,
+            ControllerAction::Down | ControllerAction::Right => self.menu_focus = 1,
+            ControllerAction::Select if self.menu_focus == 1 => self.retry_calibration(),
+            ControllerAction::Select | ControllerAction::Back => self.dismiss_calibration_alert(),
+        }
+    }
+
+    /// Handles controller input while the actions menu is open.
+    fn handle_menu_controller(&mut self, action: ControllerAction) {
+        if self.focus.is_none() {
+            self.focus = Some(0);
+            if !matches!(action, ControllerAction::Select | ControllerAction::Back) {
+                return;
+            }
+        }
+        let actions = self.bar_actions();
+        let focus = match action {
+            ControllerAction::Select => {
+                if let Some(&(action, _)) = actions.get(self.menu_focus) {
+                    self.run_bar_action(action);
+                }
+                return;
+            }
+            ControllerAction::Back => {
+                self.actions_menu = false;
+                return;
+            }
+            ControllerAction::Up => self.menu_focus.saturating_sub(actions_menu::MENU_COLUMNS),
+            ControllerAction::Down => self.menu_focus + actions_menu::MENU_COLUMNS,
+            ControllerAction::Left => self.menu_focus.saturating_sub(1),
+            ControllerAction::Right => self.menu_focus + 1,
+        };
+        self.menu_focus = focus.min(actions.len().saturating_sub(1));
+        self.actions_page = actions_menu::page_of(self.menu_focus);
+    }
+
+    /// Returns the three lines of text to mirror to the controller screen.
+    pub fn controller_lines(&self, data: &AppData) -> [String; 3] {
+        let selection = self.external.borrow().selection;
+        let route = &data.routes[selection];
+        let focused_name = |items: &[(usize, String, usize)]| {
+            self.focus
+                .and_then(|focus| items.get(focus))
+                .map(|(_, name, _)| format!("> {name}"))
+                .unwrap_or_default()
+        };
+
+        if self.calibration_alert {
+            return [
+                "Calibration".to_string(),
+                if self.menu_focus == 1 {
+                    "> Retry"
+                } else {
+                    "> Dismiss"
+                }
+                .to_string(),
+                "B: dismiss".to_string(),
+            ];
+        }
+        if self.actions_menu {
+            return [
+                "Actions".to_string(),
+                self.bar_actions()
+                    .get(self.menu_focus)
+                    .map(|(_, action)| format!("> {}", action.label))
+                    .unwrap_or_default(),
+                "B: close".to_string(),
+            ];
+        }
+        if let Some((_, action)) = self
+            .bar_focus(data)
+            .and_then(|index| self.bar_buttons().get(index).cloned())
+        {
+            return [
+                "Bottom bar".to_string(),
+                format!("> {}", action.label),
+                "A: press".to_string(),
+            ];
+        }
+
+        match &self.screen {
+            Screen::SelectCategory => [
+                "Select category".to_string(),
+                focused_name(&data.category_names),
+                String::new(),
+            ],
+            Screen::SelectRoute(category_index) => [
+                data.category_names[*category_index].1.clone(),
+                focused_name(&data.route_names_map[category_index]),
+                "B: back".to_string(),
+            ],
+            Screen::ConfirmSelection => match self
+                .focus
+                .and_then(|focus| self.checklist.get(focus).map(|item| (focus, item)))
+            {
+                Some((focus, item)) => [
+                    "Checklist".to_string(),
+                    format!(
+                        "{} {}",
+                        if self.is_checked(focus) { "[x]" } else { "[ ]" },
+                        item.label
+                    ),
+                    "A: tick  B: back".to_string(),
+                ],
+                None => [
+                    "Confirm?".to_string(),
+                    route.name.to_string(),
+                    "A: yes  B: back".to_string(),
+                ],
+            },
+            Screen::Confirmed => [
+                "Confirmed".to_string(),
+                route.name.to_string(),
+                data.category_names[route.category_index].1.clone(),
+            ],
+            Screen::Diagnostics(_) => [
+                "Diagnostics".to_string(),
+                String::new(),
+                "B: back".to_string(),
+            ],
+        }
+    }
+
+    /// Calls the interface's hooks for any changes since `previous`.
+    ///
+    /// If `previous` is `None`, the current selection is treated as new.
+    pub fn notify_changes(&mut self, previous: Option<&ExternalState>, data: &AppData) {
+        let (selection, confirmed) = {
+            let external = self.external.borrow();
+            (external.selection, external.confirmed)
+        };
+        if previous.is_none_or(|previous| previous.selection != selection) {
+            self.interface
+                .on_selection_changed(data.route_info(selection));
+        }
+        if confirmed && previous.is_none_or(|previous| !previous.confirmed) {
+            self.interface.on_confirmed(data.route_info(selection));
+        }
+    }
+
+    pub fn refresh_diagnostics(&mut self) {
+        self.diagnostics_polled_at = Instant::now();
         let interface = &*self.interface;
         if interface.diagnostics_enable() {
-            self.diagnostics = Some(interface.diagnostics_diagnostics());
+            let entries = interface.diagnostics_entries();
+            self.diagnostic_history.record(&entries);
+            self.diagnostics = Some(entries);
         } else {
             self.diagnostics = None;
         }
@@ -111,15 +1140,20 @@ pub(super) fn root_view<'a>(
     data: &'a AppData,
 ) -> impl View<crate::view::color::Color, AppState> + use<'a> {
     ZStack::new((
-        image::LOGO_CROPPED.as_ref().map(|img| {
+        data.logo.as_ref().map(|img| {
             Image::new(img)
                 .flex_frame()
-                .with_alignment(Alignment::BottomTrailing)
+                .with_alignment(match data.config.branding.logo_position {
+                    LogoPosition::TopLeading => Alignment::TopLeading,
+                    LogoPosition::TopTrailing => Alignment::TopTrailing,
+                    LogoPosition::BottomLeading => Alignment::BottomLeading,
+                    LogoPosition::BottomTrailing => Alignment::BottomTrailing,
+                })
                 .with_infinite_max_height()
                 .with_infinite_max_width()
         }),
         if matches!(state.screen, Screen::Confirmed) {
-            image::SELECTED_BACKGROUND
+            data.selected_background
                 .as_ref()
                 .map(|img| Image::new(img).flex_frame())
         } else {
@@ -133,35 +1167,31 @@ pub(super) fn root_view<'a>(
             // conditional rendering.
             ZStack::new((
                 matches!(state.screen, Screen::SelectCategory).then(|| {
-                    select_category_screen::select_category_screen(data).transition(Move::leading())
+                    select_category_screen::select_category_screen(state, data)
+                        .transition(Move::leading())
                 }),
                 match state.screen {
                     Screen::SelectRoute(category_index) => Some(
-                        select_route_screen::select_route_screen(data, category_index)
+                        select_route_screen::select_route_screen(state, data, category_index)
                             .transition(Move::trailing()),
                     ),
                     _ => None,
                 },
                 matches!(state.screen, Screen::ConfirmSelection).then(|| {
-                    confirm_selection_screen::confirm_selection_screen(
-                        data,
-                        state.external.borrow().selection,
-                    )
-                    .transition(Move::top())
-                }),
-                matches!(state.screen, Screen::Confirmed).then(|| {
-                    EmptyView
-                        .flex_frame()
-                        .with_infinite_max_height()
-                        .with_infinite_max_width()
+                    confirm_selection_screen::confirm_selection_screen(state, data)
+                        .transition(Move::top())
                 }),
+                matches!(state.screen, Screen::Confirmed)
+                    .then(|| confirmed_screen::confirmed_screen(state, data)),
                 matches!(state.screen, Screen::Diagnostics(_)).then(|| {
-                    diagnostics_screen::diagnostics_screen(state).transition(Move::bottom())
+                    diagnostics_screen::diagnostics_screen(state, data).transition(Move::bottom())
                 }),
             )),
-            bottom_bar::bottom_bar(state),
+            bottom_bar::bottom_bar(state, data),
         )),
-        calibrating_overlay::calibrating_overlay(state),
+        actions_menu::actions_menu(state, data),
+        calibrating_overlay::calibrating_overlay(state, data),
+        calibration_result_overlay::calibration_result_overlay(state, data),
     ))
     .animated(
         Animation::ease_in_out(Duration::from_millis(400)),
@@ -174,3 +1204,483 @@ pub(super) fn root_view<'a>(
         },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RouteCallback, RouteResult};
+
+    /// Names of the routes in the "Match" category, more than fit on one
+    /// page of the route grid
+    const MATCH_ROUTES: [&str; 10] = ["R0", "R1", "R2", "R3", "R4", "R5", "R6", "R7", "R8", "R9"];
+
+    fn route(category: &'static str, name: &'static str) -> Route<&'static str, ()> {
+        Route {
+            category,
+            name,
+            description: "",
+            callback: RouteCallback::Fn(|_| Box::pin(async { RouteResult::Ok(()) })),
+            time_budget: crate::MATCH_TIME_BUDGET,
+            path: None,
+            alliance: None,
+            side: None,
+        }
+    }
+
+    /// The "Match" routes followed by one "Skills" route
+    fn routes() -> Vec<Route<&'static str, ()>> {
+        let mut routes = MATCH_ROUTES
+            .iter()
+            .map(|name| route("Match", name))
+            .collect::<Vec<_>>();
+        routes.push(route("Skills", "Full run"));
+        routes
+    }
+
+    /// Builds the UI for [`routes`].
+    fn app(config: Config) -> (AppState, AppData) {
+        app_with(routes(), config)
+    }
+
+    fn app_with(routes: Vec<Route<&'static str, ()>>, config: Config) -> (AppState, AppData) {
+        let categories = crate::route::categories(&routes);
+        let state = AppState::new(
+            Rc::new(RefCell::new(ExternalState::new(0))),
+            Box::new(crate::builder::NoInterface),
+            CompetitionMode::Disabled,
+        );
+        (state, AppData::new(routes, categories, config))
+    }
+
+    /// Interface with calibration, diagnostics and some quick actions, which
+    /// records what the UI asks of it.
+    #[derive(Default)]
+    struct ActionsInterface {
+        actions: usize,
+        calibrations: Rc<RefCell<usize>>,
+        ran: Rc<RefCell<Vec<usize>>>,
+    }
+
+    impl crate::DoxaSelectInterface for ActionsInterface {
+        fn calibrating_enable(&self) -> bool {
+            true
+        }
+        fn calibrating_calibrate(&mut self) {
+            *self.calibrations.borrow_mut() += 1;
+        }
+        fn actions(&self) -> Vec<QuickAction> {
+            (0..self.actions)
+                .map(|index| QuickAction::new(format!("Action {index}")))
+                .collect()
+        }
+        fn on_action(&mut self, index: usize) {
+            self.ran.borrow_mut().push(index);
+        }
+        fn diagnostics_enable(&self) -> bool {
+            true
+        }
+    }
+
+    /// Builds the UI with an [`ActionsInterface`] of `actions` quick actions.
+    fn app_with_actions(
+        actions: usize,
+    ) -> (
+        AppState,
+        AppData,
+        Rc<RefCell<usize>>,
+        Rc<RefCell<Vec<usize>>>,
+    ) {
+        let interface = ActionsInterface {
+            actions,
+            ..ActionsInterface::default()
+        };
+        let (calibrations, ran) = (interface.calibrations.clone(), interface.ran.clone());
+        let (mut state, data) = app(Config::default());
+        state.interface = Box::new(interface);
+        state.refresh_actions();
+        (state, data, calibrations, ran)
+    }
+
+    /// Builds the UI with a checklist of `items` manual items, on the
+    /// confirmation screen.
+    fn confirm_screen(items: usize) -> (AppState, AppData) {
+        let (mut state, data) = app(Config {
+            checklist: (0..items)
+                .map(|index| ChecklistItem::manual(format!("Item {index}")))
+                .collect(),
+            ..Config::default()
+        });
+        state.refresh_checklist(&data);
+        state.navigate(Screen::ConfirmSelection);
+        (state, data)
+    }
+
+    #[test]
+    fn first_press_only_shows_focus() {
+        let (mut state, data) = app(Config::default());
+        state.handle_controller(ControllerAction::Down, &data);
+        assert_eq!(state.focus, Some(0));
+        assert_eq!(state.screen, Screen::SelectCategory);
+    }
+
+    #[test]
+    fn focus_is_clamped_to_items() {
+        let (mut state, data) = app(Config::default());
+        state.focus = Some(0);
+        // Two categories in one row
+        for _ in 0..3 {
+            state.handle_controller(ControllerAction::Right, &data);
+        }
+        assert_eq!(state.focus, Some(1));
+        state.handle_controller(ControllerAction::Down, &data);
+        assert_eq!(state.focus, Some(1));
+        state.handle_controller(ControllerAction::Up, &data);
+        assert_eq!(state.focus, Some(0));
+        state.handle_controller(ControllerAction::Left, &data);
+        assert_eq!(state.focus, Some(0));
+    }
+
+    #[test]
+    fn focus_moves_between_rows_and_pages() {
+        let (mut state, data) = app(Config::default());
+        state.navigate(Screen::SelectRoute(0));
+        let (_, layout) = state.grid(&data).unwrap();
+        let columns = layout.columns;
+        let page_size = layout.page_size();
+        assert!(page_size < MATCH_ROUTES.len());
+
+        state.focus = Some(page_size - 1);
+        state.handle_controller(ControllerAction::Right, &data);
+        assert_eq!(state.focus, Some(page_size));
+        assert_eq!(state.page, 1);
+        state.handle_controller(ControllerAction::Up, &data);
+        assert_eq!(state.focus, Some(page_size - columns));
+        assert_eq!(state.page, 0);
+        // Moving down past the last item stops at it
+        for _ in 0..MATCH_ROUTES.len() {
+            state.handle_controller(ControllerAction::Down, &data);
+        }
+        assert_eq!(state.focus, Some(MATCH_ROUTES.len() - 1));
+        assert_eq!(state.page, layout.page_of(MATCH_ROUTES.len() - 1));
+    }
+
+    #[test]
+    fn checklist_focus_ends_on_confirm_button() {
+        let (mut state, data) = confirm_screen(5);
+        state.focus = Some(3);
+        state.handle_controller(ControllerAction::Down, &data);
+        assert_eq!(state.focus, Some(4));
+        assert_eq!(
+            state.checklist_page,
+            confirm_selection_screen::checklist_page_of(4)
+        );
+        // The "Confirm" button comes after the last item, and nothing after it
+        state.handle_controller(ControllerAction::Down, &data);
+        state.handle_controller(ControllerAction::Down, &data);
+        assert_eq!(state.focus, Some(5));
+    }
+
+    #[test]
+    fn select_opens_category_then_route() {
+        let (mut state, data) = app(Config::default());
+        state.handle_controller(ControllerAction::Select, &data);
+        assert_eq!(state.screen, Screen::SelectRoute(0));
+        assert_eq!(state.focus, Some(0));
+
+        state.focus = Some(2);
+        state.handle_controller(ControllerAction::Select, &data);
+        assert_eq!(state.screen, Screen::ConfirmSelection);
+        assert_eq!(state.external.borrow().selection, 2);
+    }
+
+    #[test]
+    fn select_ticks_checklist_before_confirming() {
+        let (mut state, data) = confirm_screen(1);
+        state.focus = Some(1);
+        // Blocked until the item is ticked
+        state.handle_controller(ControllerAction::Select, &data);
+        assert_eq!(state.screen, Screen::ConfirmSelection);
+        assert!(!state.external.borrow().confirmed);
+
+        state.focus = Some(0);
+        state.handle_controller(ControllerAction::Select, &data);
+        assert!(state.is_checked(0));
+        state.focus = Some(1);
+        state.handle_controller(ControllerAction::Select, &data);
+        assert_eq!(state.screen, Screen::Confirmed);
+        assert!(state.external.borrow().confirmed);
+    }
+
+    #[test]
+    fn publishes_confirm_blocker() {
+        let (mut state, data) = confirm_screen(2);
+        state.publish_confirm_blocker(&data);
+        assert_eq!(
+            state.external.borrow().confirm_blocker,
+            Some(ConfirmError::ChecklistIncomplete { remaining: 2 })
+        );
+
+        state.toggle_check(0);
+        state.toggle_check(1);
+        state.publish_confirm_blocker(&data);
+        assert_eq!(state.external.borrow().confirm_blocker, None);
+    }
+
+    #[test]
+    fn back_returns_to_category_selection() {
+        let (mut state, data) = app(Config::default());
+        state.handle_controller(ControllerAction::Back, &data);
+        assert_eq!(state.screen, Screen::SelectCategory);
+
+        for screen in [Screen::SelectRoute(1), Screen::ConfirmSelection] {
+            state.navigate(screen);
+            state.handle_controller(ControllerAction::Back, &data);
+            assert_eq!(state.screen, Screen::SelectCategory);
+        }
+    }
+
+    #[test]
+    fn back_unconfirms_route() {
+        let (mut state, data) = confirm_screen(0);
+        state.handle_controller(ControllerAction::Select, &data);
+        assert_eq!(state.screen, Screen::Confirmed);
+        state.handle_controller(ControllerAction::Back, &data);
+        assert_eq!(state.screen, Screen::SelectCategory);
+        assert!(!state.external.borrow().confirmed);
+    }
+
+    #[test]
+    fn diagnostics_pages_instead_of_focus() {
+        let (mut state, data) = app(Config::default());
+        state.navigate(Screen::SelectRoute(0));
+        state.toggle_diagnostics();
+        state.handle_controller(ControllerAction::Down, &data);
+        // There's only one page without any diagnostics
+        assert_eq!(state.page, 0);
+        assert_eq!(state.focus, None);
+
+        state.handle_controller(ControllerAction::Back, &data);
+        assert_eq!(state.screen, Screen::SelectRoute(0));
+    }
+
+    #[test]
+    fn recents_are_capped() {
+        let (mut state, _) = app(Config::default());
+        for route in 0..MAX_RECENTS + 2 {
+            state.push_recent(route);
+        }
+        assert_eq!(state.recents.len(), MAX_RECENTS);
+        assert_eq!(state.recents[0], MAX_RECENTS + 1);
+    }
+
+    #[test]
+    fn recents_have_no_duplicates() {
+        let (mut state, _) = app(Config::default());
+        for route in [1, 2, 1] {
+            state.push_recent(route);
+        }
+        assert_eq!(state.recents, [1, 2]);
+    }
+
+    #[test]
+    fn confirming_adds_recent() {
+        let (mut state, data) = confirm_screen(0);
+        state.external.borrow_mut().selection = 3;
+        state.handle_controller(ControllerAction::Select, &data);
+        assert_eq!(state.recents, [3]);
+    }
+
+    #[test]
+    fn shortcuts_put_favorites_first() {
+        let (mut state, _) = app(Config::default());
+        state.favorites = vec![2];
+        state.recents = vec![4, 2, 5];
+        // Favorites that are also recent are only shown once
+        assert_eq!(state.shortcuts(), [2, 4, 5]);
+
+        state.favorites = vec![0, 1, 2];
+        assert_eq!(state.shortcuts().len(), MAX_SHORTCUTS);
+    }
+
+    #[test]
+    fn shortcuts_follow_routes_by_name() {
+        let (_, data) = app(Config::default());
+        let saved = |routes: &[usize]| {
+            routes
+                .iter()
+                .map(|&index| {
+                    let (category, name) = data.saved_route(index);
+                    persist::SavedRoute {
+                        category: category.to_string(),
+                        name: name.to_string(),
+                    }
+                })
+                .collect()
+        };
+        let shortcuts = persist::SavedShortcuts {
+            favorites: saved(&[1, 10]),
+            recents: saved(&[3, 1, 2, 0]),
+        };
+
+        // The route list is reordered, and "R2" is removed
+        let mut reordered = routes();
+        reordered.reverse();
+        reordered.retain(|route| route.name != "R2");
+        let (mut state, reordered) = app_with(reordered, Config::default());
+        state.set_shortcuts(&shortcuts, &reordered);
+
+        let names = |routes: &[usize]| {
+            routes
+                .iter()
+                .map(|&index| reordered.saved_route(index).1)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&state.favorites), ["R1", "Full run"]);
+        assert_eq!(names(&state.recents), ["R3", "R1", "R0"]);
+    }
+
+    #[test]
+    fn bar_counts_built_in_actions() {
+        let (state, ..) = app_with_actions(1);
+        let buttons = state
+            .bar_buttons()
+            .into_iter()
+            .map(|(action, _)| action)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            buttons,
+            [
+                BarAction::Quick(0),
+                BarAction::Calibrate,
+                BarAction::Diagnostics
+            ]
+        );
+    }
+
+    #[test]
+    fn built_in_actions_overflow_into_menu() {
+        let (state, ..) = app_with_actions(2);
+        assert!(state.bar_actions().len() > actions_menu::MAX_BAR_ACTIONS);
+        let buttons = state.bar_buttons();
+        assert_eq!(buttons.len(), 1);
+        assert_eq!(buttons[0].0, BarAction::Menu);
+    }
+
+    #[test]
+    fn focus_moves_from_items_to_bar() {
+        let (mut state, data, calibrations, _) = app_with_actions(1);
+        // Two categories, then the bar
+        state.focus = Some(1);
+        state.handle_controller(ControllerAction::Down, &data);
+        assert_eq!(state.focus, Some(2));
+        assert_eq!(state.bar_focus(&data), Some(0));
+        state.handle_controller(ControllerAction::Right, &data);
+        state.handle_controller(ControllerAction::Select, &data);
+        assert_eq!(*calibrations.borrow(), 1);
+        assert_eq!(state.screen, Screen::SelectCategory);
+
+        // The bar is a single row, so the focus stops at its last button
+        state.handle_controller(ControllerAction::Right, &data);
+        state.handle_controller(ControllerAction::Right, &data);
+        assert_eq!(state.bar_focus(&data), Some(2));
+        state.handle_controller(ControllerAction::Up, &data);
+        assert_eq!(state.focus, Some(1));
+    }
+
+    #[test]
+    fn bar_focus_on_diagnostics_screen() {
+        let (mut state, data, _, ran) = app_with_actions(1);
+        state.navigate(Screen::Diagnostics(Box::new(Screen::Confirmed)));
+        state.focus = Some(0);
+        // Up and down page, left and right move along the bar
+        state.handle_controller(ControllerAction::Down, &data);
+        assert_eq!(state.focus, Some(0));
+        state.handle_controller(ControllerAction::Select, &data);
+        assert_eq!(*ran.borrow(), [0]);
+        state.handle_controller(ControllerAction::Right, &data);
+        state.handle_controller(ControllerAction::Right, &data);
+        state.handle_controller(ControllerAction::Select, &data);
+        assert_eq!(state.screen, Screen::Confirmed);
+    }
+
+    #[test]
+    fn menu_takes_focus_until_closed() {
+        let (mut state, data, _, ran) = app_with_actions(2);
+        state.navigate(Screen::Confirmed);
+        state.focus = Some(0);
+        state.handle_controller(ControllerAction::Select, &data);
+        assert!(state.actions_menu);
+
+        state.handle_controller(ControllerAction::Right, &data);
+        assert_eq!(state.menu_focus, 1);
+        state.handle_controller(ControllerAction::Select, &data);
+        assert_eq!(*ran.borrow(), [1]);
+        assert!(!state.actions_menu);
+
+        state.run_bar_action(BarAction::Menu);
+        assert_eq!(state.menu_focus, 0);
+        state.handle_controller(ControllerAction::Back, &data);
+        assert!(!state.actions_menu);
+        assert_eq!(state.screen, Screen::Confirmed);
+    }
+
+    #[test]
+    fn alert_focuses_dismiss_then_retry() {
+        let (mut state, data, calibrations, _) = app_with_actions(0);
+        state.calibration_alert = true;
+        state.handle_controller(ControllerAction::Right, &data);
+        // The first press only shows the focus
+        assert_eq!(state.menu_focus, 0);
+        state.handle_controller(ControllerAction::Right, &data);
+        assert_eq!(state.menu_focus, 1);
+        state.handle_controller(ControllerAction::Select, &data);
+        assert_eq!(*calibrations.borrow(), 1);
+        assert!(!state.calibration_alert);
+        assert_eq!(state.menu_focus, 0);
+
+        state.calibration_alert = true;
+        state.handle_controller(ControllerAction::Select, &data);
+        assert!(!state.calibration_alert);
+        assert_eq!(*calibrations.borrow(), 1);
+    }
+
+    /// Interface with one numeric diagnostic entry.
+    struct DiagnosticsInterface;
+
+    impl crate::DoxaSelectInterface for DiagnosticsInterface {
+        fn diagnostics_enable(&self) -> bool {
+            true
+        }
+        fn diagnostics_entries(&self) -> Vec<DiagnosticEntry> {
+            vec![DiagnosticEntry::number("Battery", 12.4)]
+        }
+    }
+
+    #[test]
+    fn live_diagnostics_record_on_every_screen() {
+        let (mut state, data) = app(Config {
+            diagnostics_interval: Some(Duration::ZERO),
+            ..Config::default()
+        });
+        state.interface = Box::new(DiagnosticsInterface);
+        let battery = DiagnosticEntry::number("Battery", 12.4);
+
+        // Nothing to rebuild while the diagnostics aren't shown
+        assert!(!state.poll_diagnostics(&data));
+        assert!(!state.poll_diagnostics(&data));
+        assert_eq!(
+            state.diagnostic_history.get(&battery).map(|s| s.len()),
+            Some(2)
+        );
+
+        // The sparkline gains a sample every poll, even if the value is the
+        // same
+        state.navigate(Screen::Diagnostics(Box::new(Screen::SelectCategory)));
+        assert!(state.poll_diagnostics(&data));
+        assert!(state.poll_diagnostics(&data));
+        assert_eq!(
+            state.diagnostic_history.get(&battery).map(|s| s.len()),
+            Some(4)
+        );
+    }
+}
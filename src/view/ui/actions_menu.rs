@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use buoyant::{transition::Move, view::prelude::*};
+
+use crate::{
+    theme::ColorScheme,
+    view::{
+        color,
+        font::Fonts,
+        spacing,
+        ui::{
+            bottom_bar::BarAction,
+            button::{self, ButtonStyle},
+            AppData, AppState,
+        },
+    },
+    ActionStyle, QuickAction,
+};
+
+/// Actions shown in the bottom bar before they're all moved to the menu
+///
+/// This counts the built-in "Calibrate" and "Diagnostics" buttons as well as
+/// the interface's quick actions.
+pub const MAX_BAR_ACTIONS: usize = 3;
+
+/// Columns of the actions menu
+pub const MENU_COLUMNS: usize = 2;
+/// Rows of the actions menu
+const MENU_ROWS: usize = 3;
+
+/// Row indices for `ForEach`, which requires a slice that outlives the view
+static ROWS: [usize; MENU_ROWS] = [0, 1, 2];
+
+/// Number of pages needed to show `len` actions in the menu, at least one.
+pub fn page_count(len: usize) -> usize {
+    len.div_ceil(MENU_COLUMNS * MENU_ROWS).max(1)
+}
+
+/// Page of the menu that the action at `index` is on.
+pub fn page_of(index: usize) -> usize {
+    index / (MENU_COLUMNS * MENU_ROWS)
+}
+
+/// Button of the bottom bar or actions menu, labeled and styled like `label`.
+pub fn action_button(
+    action: BarAction,
+    label: QuickAction,
+    focused: bool,
+    scheme: &ColorScheme,
+    fonts: &Fonts,
+) -> impl View<color::Color, AppState> {
+    let style = match label.style {
+        ActionStyle::Normal => ButtonStyle::new(scheme, fonts),
+        ActionStyle::Primary => ButtonStyle::filled(scheme, fonts),
+        ActionStyle::Destructive => ButtonStyle::destructive(scheme, fonts),
+    };
+    let style = ButtonStyle {
+        focused,
+        ..if label.enabled {
+            style
+        } else {
+            style.disabled(scheme)
+        }
+    };
+    button::button(label.label, style, move |state: &mut AppState| {
+        state.run_bar_action(action);
+    })
+}
+
+/// Menu of all the actions, for when there are too many for the bottom bar.
+///
+/// Six actions fit on a page, so longer lists are paged.
+pub fn actions_menu(state: &AppState, data: &AppData) -> impl View<color::Color, AppState> {
+    let scheme = *data.scheme();
+    let fonts = *data.fonts();
+    let actions = state.actions_menu.then(|| state.bar_actions());
+    let focused = state.focus.map(|_| state.menu_focus);
+    let shown = actions.is_some();
+
+    actions
+        .map(|actions| {
+            let page_count = page_count(actions.len());
+            let page = state.actions_page.min(page_count - 1);
+            let first_row = page * MENU_ROWS;
+            let rows = actions
+                .len()
+                .div_ceil(MENU_COLUMNS)
+                .saturating_sub(first_row)
+                .min(MENU_ROWS);
+            RoundedRectangle::new(32)
+                .foreground_color(scheme.surface_container_high)
+                .padding(Edges::All, 24)
+                .overlay(
+                    Alignment::Center,
+                    VStack::new((
+                        HStack::new((
+                            Text::new("Actions", fonts.heading)
+                                .with_font_size(fonts.scale.heading)
+                                .foreground_color(scheme.on_surface)
+                                .hint_background_color(scheme.surface_container_high),
+                            Spacer::default(),
+                            (page_count > 1).then(|| {
+                                HStack::new((
+                                    button::button(
+                                        "Prev",
+                                        ButtonStyle::new(&scheme, &fonts),
+                                        move |state: &mut AppState| {
+                                            state.actions_page = page.saturating_sub(1);
+                                        },
+                                    ),
+                                    Text::new(
+                                        format!("{}/{}", page + 1, page_count),
+                                        fonts.regular,
+                                    )
+                                    .with_font_size(fonts.scale.caption)
+                                    .foreground_color(scheme.on_surface)
+                                    .hint_background_color(scheme.surface_container_high),
+                                    button::button(
+                                        "Next",
+                                        ButtonStyle::new(&scheme, &fonts),
+                                        move |state: &mut AppState| {
+                                            state.actions_page = (page + 1).min(page_count - 1);
+                                        },
+                                    ),
+                                ))
+                                .with_spacing(spacing::ELEMENT)
+                            }),
+                            button::button(
+                                "Close",
+                                ButtonStyle::new(&scheme, &fonts),
+                                |state: &mut AppState| {
+                                    state.actions_menu = false;
+                                },
+                            ),
+                        ))
+                        .with_spacing(spacing::ELEMENT),
+                        ForEach::<MENU_ROWS>::new_vertical(&ROWS[..rows], move |row: &usize| {
+                            let cell = |column: usize| {
+                                let index = (first_row + row) * MENU_COLUMNS + column;
+                                actions.get(index).cloned().map(|(action, label)| {
+                                    let focused = focused == Some(index);
+                                    action_button(action, label, focused, &scheme, &fonts)
+                                        .flex_infinite_width(HorizontalAlignment::Center)
+                                })
+                            };
+                            HStack::new((cell(0), cell(1))).with_spacing(spacing::ELEMENT)
+                        })
+                        .with_spacing(spacing::ELEMENT),
+                    ))
+                    .with_spacing(spacing::ELEMENT)
+                    .padding(Edges::All, 48),
+                )
+                .transition(Move::bottom())
+        })
+        .animated(Animation::ease_in_out(Duration::from_millis(400)), shown)
+}
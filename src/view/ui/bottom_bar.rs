@@ -1,68 +1,65 @@
 use buoyant::{match_view, view::prelude::*};
 
 use crate::view::{
-    color, font, spacing,
+    color, spacing,
     ui::{
+        actions_menu,
         button::{self, ButtonStyle},
-        AppState, Screen,
+        AppData, AppState, Screen,
     },
 };
 
-pub fn bottom_bar(state: &AppState) -> impl View<color::Color, AppState> {
+/// What a button in the bottom bar or actions menu does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarAction {
+    /// Runs the interface's quick action at this index
+    Quick(usize),
+    /// Starts calibrating
+    Calibrate,
+    /// Opens or closes the diagnostics screen
+    Diagnostics,
+    /// Opens the menu of actions that don't fit in the bar
+    Menu,
+}
+
+pub fn bottom_bar<'a>(
+    state: &AppState,
+    data: &'a AppData,
+) -> impl View<color::Color, AppState> + use<'a> {
+    let scheme = data.scheme();
+    let fonts = data.fonts();
+    let buttons = state.bar_buttons();
+    let focused = state.bar_focus(data);
+    let action = |index: usize| {
+        buttons.get(index).cloned().map(|(action, label)| {
+            actions_menu::action_button(action, label, focused == Some(index), scheme, fonts)
+        })
+    };
     HStack::new((
         match_view!(state.screen, {
             Screen::Confirmed => button::button(
                 "Change route",
-                ButtonStyle::default(),
+                ButtonStyle::new(scheme, fonts),
                 |state: &mut AppState| {
-                    state.screen = crate::view::ui::Screen::SelectCategory;
+                    state.change_route();
                 },
             ),
-            _ => Text::new("99484A DOXA Robotics", &*font::MONTSERRAT)
-                .with_font_size(font::SIZE_CAPTION)
-                .foreground_color(color::M3_ON_SURFACE),
+            _ => fonts.text(fonts.regular, &data.footer, move |footer, font| {
+                Text::new(footer, font)
+                    .with_font_size(fonts.scale.caption)
+                    .foreground_color(scheme.on_surface)
+            }),
         }),
         Spacer::default(),
-        state.interface.calibrating_enable().then(|| {
-            button::button(
-                "Calibrate",
-                ButtonStyle::default(),
-                |state: &mut AppState| {
-                    state.interface.calibrating_calibrate();
-                },
-            )
-        }),
-        state.interface.diagnostics_enable().then(|| {
-            button::button(
-                match state.screen {
-                    Screen::Diagnostics(_) => "Exit diagnostics",
-                    _ => "Diagnostics",
-                },
-                match state.screen {
-                    Screen::Diagnostics(_) => ButtonStyle::filled(),
-                    _ => ButtonStyle::default(),
-                },
-                |state: &mut AppState| {
-                    state.screen = match &state.screen {
-                        Screen::Diagnostics(previous_screen) => {
-                            // If already in diagnostics, go back to previous screen
-                            *previous_screen.clone()
-                        }
-                        screen => {
-                            // Otherwise, go to diagnostics, saving current screen
-                            let screen = screen.clone();
-                            state.refresh_diagnostics();
-                            crate::view::ui::Screen::Diagnostics(Box::new(screen))
-                        }
-                    }
-                },
-            )
-        }),
+        // One slot for each of `MAX_BAR_ACTIONS`
+        action(0),
+        action(1),
+        action(2),
     ))
     .with_spacing(spacing::COMPONENT)
     .flex_infinite_width(HorizontalAlignment::Center)
     .padding(Edges::All, spacing::COMPONENT)
-    .background_color(color::M3_SURFACE_CONTAINER_HIGHEST, Capsule)
+    .background_color(scheme.surface_container_highest, Capsule)
     .padding(Edges::Horizontal, spacing::EDGE)
     .padding(Edges::Bottom, spacing::EDGE)
     .geometry_group()
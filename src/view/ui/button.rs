@@ -2,9 +2,12 @@ use std::time::Duration;
 
 use buoyant::{primitives::UnitPoint, view::prelude::*};
 
-use crate::view::{
-    color::{self},
-    font,
+use crate::{
+    theme::ColorScheme,
+    view::{
+        color::{self},
+        font::Fonts,
+    },
 };
 
 pub struct ButtonStyle {
@@ -16,83 +19,122 @@ pub struct ButtonStyle {
     pub background_pressed: color::Color,
     pub foreground_pressed: color::Color,
 
+    /// Whether the button has controller focus
+    pub focused: bool,
+    pub background_focused: color::Color,
+    pub foreground_focused: color::Color,
+
     pub animation_duration: Duration,
 
+    pub fonts: Fonts,
     pub font_size: u32,
 }
 
-impl Default for ButtonStyle {
-    fn default() -> Self {
+impl ButtonStyle {
+    pub fn new(scheme: &ColorScheme, fonts: &Fonts) -> Self {
         Self {
             height: 24,
             horizontal_padding: 12,
             vertical_padding: 8,
-            background: color::M3_SECONDARY_CONTAINER,
-            foreground: color::M3_ON_SECONDARY_CONTAINER,
-            background_pressed: color::M3_SECONDARY_CONTAINER,
-            foreground_pressed: color::M3_ON_SECONDARY_CONTAINER,
+            background: scheme.secondary_container,
+            foreground: scheme.on_secondary_container,
+            background_pressed: scheme.secondary_container,
+            foreground_pressed: scheme.on_secondary_container,
+            focused: false,
+            background_focused: scheme.tertiary,
+            foreground_focused: scheme.on_tertiary,
             animation_duration: Duration::from_millis(200),
-            font_size: font::SIZE_CAPTION,
+            fonts: *fonts,
+            font_size: fonts.scale.caption,
         }
     }
-}
 
-impl ButtonStyle {
-    pub fn large() -> Self {
+    pub fn large(scheme: &ColorScheme, fonts: &Fonts) -> Self {
         Self {
             height: 32,
             horizontal_padding: 16,
             vertical_padding: 12,
-            font_size: font::SIZE_BODY,
-            ..Self::default()
+            font_size: fonts.scale.body,
+            ..Self::new(scheme, fonts)
         }
     }
 
-    pub fn filled_large() -> Self {
+    pub fn filled_large(scheme: &ColorScheme, fonts: &Fonts) -> Self {
         Self {
-            background: color::M3_PRIMARY,
-            foreground: color::M3_ON_PRIMARY,
-            background_pressed: color::M3_PRIMARY,
-            foreground_pressed: color::M3_ON_PRIMARY,
+            background: scheme.primary,
+            foreground: scheme.on_primary,
+            background_pressed: scheme.primary,
+            foreground_pressed: scheme.on_primary,
             height: 32,
             horizontal_padding: 16,
             vertical_padding: 12,
-            font_size: font::SIZE_BODY,
-            ..Self::default()
+            font_size: fonts.scale.body,
+            ..Self::new(scheme, fonts)
+        }
+    }
+
+    pub fn filled(scheme: &ColorScheme, fonts: &Fonts) -> Self {
+        Self {
+            background: scheme.primary,
+            foreground: scheme.on_primary,
+            background_pressed: scheme.primary,
+            foreground_pressed: scheme.on_primary,
+            ..Self::new(scheme, fonts)
         }
     }
 
-    pub fn filled() -> Self {
+    pub fn destructive(scheme: &ColorScheme, fonts: &Fonts) -> Self {
         Self {
-            background: color::M3_PRIMARY,
-            foreground: color::M3_ON_PRIMARY,
-            background_pressed: color::M3_PRIMARY,
-            foreground_pressed: color::M3_ON_PRIMARY,
-            ..Self::default()
+            background: scheme.error,
+            foreground: scheme.on_error,
+            background_pressed: scheme.error,
+            foreground_pressed: scheme.on_error,
+            ..Self::new(scheme, fonts)
+        }
+    }
+
+    /// Greys out the button to show that tapping it does nothing.
+    pub fn disabled(self, scheme: &ColorScheme) -> Self {
+        Self {
+            background: scheme.surface_container_high,
+            foreground: scheme.outline,
+            background_pressed: scheme.surface_container_high,
+            foreground_pressed: scheme.outline,
+            ..self
         }
     }
 }
 
-pub fn button<'a, C: 'a, OnTapFn>(
-    label: &'a str,
+pub fn button<'a, C: 'a, T, OnTapFn>(
+    label: T,
     style: ButtonStyle,
     on_tap: OnTapFn,
 ) -> impl View<color::Color, C> + 'a
 where
+    T: AsRef<str> + Clone + 'a,
     OnTapFn: Fn(&mut C) + 'a,
 {
+    let (background, foreground) = if style.focused {
+        (style.background_focused, style.foreground_focused)
+    } else {
+        (style.background, style.foreground)
+    };
     Button::new(on_tap, move |is_pressed: bool| {
-        Text::new(label, &*font::MONTSERRAT)
-            .with_font_size(style.font_size)
-            .foreground_color(if is_pressed {
-                style.foreground_pressed
-            } else {
-                style.foreground
-            })
-            .hint_background_color(if is_pressed {
-                style.background_pressed
-            } else {
-                style.background
+        style
+            .fonts
+            .text(style.fonts.regular, label.as_ref(), move |label, font| {
+                Text::new(label, font)
+                    .with_font_size(style.font_size)
+                    .foreground_color(if is_pressed {
+                        style.foreground_pressed
+                    } else {
+                        foreground
+                    })
+                    .hint_background_color(if is_pressed {
+                        style.background_pressed
+                    } else {
+                        background
+                    })
             })
             .padding(Edges::Horizontal, style.horizontal_padding)
             .padding(Edges::Vertical, style.vertical_padding)
@@ -104,7 +146,7 @@ where
                     .foreground_color(if is_pressed {
                         style.background_pressed
                     } else {
-                        style.background
+                        background
                     })
                     .scale_effect(if is_pressed { 0.9 } else { 1.0 }, UnitPoint::center())
                     .animated(Animation::ease_out(style.animation_duration), is_pressed),
@@ -2,27 +2,95 @@ use std::time::Duration;
 
 use buoyant::{transition::Move, view::prelude::*};
 
-use crate::view::{color, font, spacing, ui::AppState};
+use crate::view::{
+    color, framebuffer, spacing,
+    ui::{
+        button::{self, ButtonStyle},
+        AppData, AppState,
+    },
+};
 
-pub fn calibrating_overlay(state: &AppState) -> impl View<color::Color, AppState> {
-    let calibrating = state.external.borrow().calibrating;
+/// Margin around the card
+const MARGIN: u32 = 48;
+/// Width of the status, inside the card's margin
+const STATUS_WIDTH: u32 = framebuffer::WIDTH - 2 * MARGIN;
+/// Width of the calibration progress bar
+const PROGRESS_WIDTH: u32 = 240;
+/// Height of the calibration progress bar
+const PROGRESS_HEIGHT: u32 = 8;
+
+pub fn calibrating_overlay(state: &AppState, data: &AppData) -> impl View<color::Color, AppState> {
+    let scheme = *data.scheme();
+    let fonts = *data.fonts();
+    let external = state.external.borrow();
+    let calibrating = external.calibrating;
+    let progress = external.calibration_progress;
+    let status = external.calibration_status.clone();
+    let cancellable = state.interface.calibrating_cancellable();
     calibrating
         // If calibrating is true, then render
         .then(|| {
             RoundedRectangle::new(32)
-                .foreground_color(color::M3_ERROR_CONTAINER)
-                .padding(Edges::All, 48)
+                .foreground_color(scheme.error_container)
+                .padding(Edges::All, MARGIN)
                 .overlay(
                     Alignment::Center,
                     VStack::new((
-                        Text::new("Calibrating...", &*font::MONTSERRAT)
-                            .with_font_size(font::SIZE_HEADING)
-                            .foreground_color(color::M3_ON_ERROR_CONTAINER)
-                            .hint_background_color(color::M3_ERROR_CONTAINER),
-                        Text::new("Please do not move the robot.", &*font::MONTSERRAT)
-                            .with_font_size(font::SIZE_BODY)
-                            .foreground_color(color::M3_ON_SURFACE_VARIANT)
-                            .hint_background_color(color::M3_ERROR_CONTAINER),
+                        Text::new("Calibrating...", fonts.heading)
+                            .with_font_size(fonts.scale.heading)
+                            .foreground_color(scheme.on_error_container)
+                            .hint_background_color(scheme.error_container),
+                        Text::new("Please do not move the robot.", fonts.regular)
+                            .with_font_size(fonts.scale.body)
+                            .foreground_color(scheme.on_surface_variant)
+                            .hint_background_color(scheme.error_container),
+                        status.map(|status| {
+                            fonts.wrapped_text(
+                                fonts.regular,
+                                fonts.scale.caption,
+                                STATUS_WIDTH,
+                                &status,
+                                move |status, font| {
+                                    Text::new(status, font)
+                                        .with_font_size(fonts.scale.caption)
+                                        .foreground_color(scheme.on_error_container)
+                                        .hint_background_color(scheme.error_container)
+                                },
+                            )
+                        }),
+                        progress.map(|progress| {
+                            let filled = PROGRESS_WIDTH * progress as u32 / 1000;
+                            Capsule
+                                .foreground_color(scheme.outline_variant)
+                                .flex_frame()
+                                .with_min_width(PROGRESS_WIDTH)
+                                .with_max_width(PROGRESS_WIDTH)
+                                .with_min_height(PROGRESS_HEIGHT)
+                                .with_max_height(PROGRESS_HEIGHT)
+                                .overlay(
+                                    Alignment::Leading,
+                                    Capsule
+                                        .foreground_color(scheme.error)
+                                        .flex_frame()
+                                        .with_min_width(filled)
+                                        .with_max_width(filled)
+                                        .with_min_height(PROGRESS_HEIGHT)
+                                        .with_max_height(PROGRESS_HEIGHT)
+                                        .animated(
+                                            Animation::linear(Duration::from_millis(200)),
+                                            progress,
+                                        ),
+                                )
+                        }),
+                        cancellable.then(|| {
+                            button::button(
+                                "Cancel",
+                                ButtonStyle::new(&scheme, &fonts),
+                                |state: &mut AppState| {
+                                    state.interface.calibrating_cancel();
+                                },
+                            )
+                        }),
                     ))
                     .with_spacing(spacing::ELEMENT),
                 )
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use buoyant::{transition::Move, view::prelude::*};
+
+use crate::{
+    view::{
+        color, framebuffer, spacing,
+        ui::{
+            button::{self, ButtonStyle},
+            AppData, AppState,
+        },
+    },
+    CalibrationResult,
+};
+
+/// Margin around the card
+const MARGIN: u32 = 48;
+/// Padding on either side of the card's contents
+const PADDING: u32 = 64;
+/// Width of the reason, inside the card's margin and padding
+const REASON_WIDTH: u32 = framebuffer::WIDTH - 2 * MARGIN - 2 * PADDING;
+
+pub fn calibration_result_overlay(
+    state: &AppState,
+    data: &AppData,
+) -> impl View<color::Color, AppState> {
+    let scheme = *data.scheme();
+    let fonts = *data.fonts();
+    let result = state
+        .calibration_alert
+        .then(|| state.external.borrow().calibration.clone())
+        .flatten();
+    let shown = result.is_some();
+    // "Dismiss" comes before "Retry" in focus order
+    let focused = state.focus.map(|_| state.menu_focus.min(1));
+
+    result
+        .and_then(|result| match result {
+            CalibrationResult::Success => None,
+            CalibrationResult::Failed(reason) => Some((
+                "Calibration failed",
+                reason,
+                scheme.error_container,
+                scheme.on_error_container,
+            )),
+            CalibrationResult::Warning(reason) => Some((
+                "Calibration warning",
+                reason,
+                scheme.tertiary_container,
+                scheme.on_tertiary_container,
+            )),
+        })
+        .map(|(title, reason, background, foreground)| {
+            RoundedRectangle::new(32)
+                .foreground_color(background)
+                .padding(Edges::All, MARGIN)
+                .overlay(
+                    Alignment::Center,
+                    VStack::new((
+                        Text::new(title, fonts.heading)
+                            .with_font_size(fonts.scale.heading)
+                            .foreground_color(foreground)
+                            .hint_background_color(background),
+                        fonts.wrapped_text(
+                            fonts.regular,
+                            fonts.scale.body,
+                            REASON_WIDTH,
+                            &reason,
+                            move |reason, font| {
+                                Text::new(reason, font)
+                                    .with_font_size(fonts.scale.body)
+                                    .multiline_text_alignment(
+                                        buoyant::view::HorizontalTextAlignment::Center,
+                                    )
+                                    .foreground_color(foreground)
+                                    .hint_background_color(background)
+                            },
+                        ),
+                        HStack::new((
+                            button::button(
+                                "Dismiss",
+                                ButtonStyle {
+                                    focused: focused == Some(0),
+                                    ..ButtonStyle::new(&scheme, &fonts)
+                                },
+                                |state: &mut AppState| {
+                                    state.dismiss_calibration_alert();
+                                },
+                            ),
+                            button::button(
+                                "Retry",
+                                ButtonStyle {
+                                    focused: focused == Some(1),
+                                    ..ButtonStyle::filled(&scheme, &fonts)
+                                },
+                                |state: &mut AppState| {
+                                    state.retry_calibration();
+                                },
+                            ),
+                        ))
+                        .with_spacing(spacing::ELEMENT),
+                    ))
+                    .with_spacing(spacing::ELEMENT)
+                    .padding(Edges::Horizontal, PADDING),
+                )
+                .transition(Move::bottom())
+        })
+        .animated(Animation::ease_in_out(Duration::from_millis(800)), shown)
+}
@@ -2,11 +2,17 @@ use std::time::Duration;
 
 use buoyant::{primitives::UnitPoint, view::prelude::*};
 
-use crate::view::{
-    color::{self},
-    font,
+use crate::{
+    theme::ColorScheme,
+    view::{
+        color::{self},
+        font::Fonts,
+    },
 };
 
+/// Space between the edge of a card and its label
+pub const PADDING: u32 = 16;
+
 pub struct CardStyle {
     pub height: u32,
     pub padding: u32,
@@ -19,25 +25,35 @@ pub struct CardStyle {
     pub border_width: u32,
     pub border_color: color::Color,
 
+    /// Whether the card has controller focus
+    pub focused: bool,
+    pub focus_border_width: u32,
+    pub focus_border_color: color::Color,
+
     pub animation_duration: Duration,
 
+    pub fonts: Fonts,
     pub font_size: u32,
 }
 
-impl Default for CardStyle {
-    fn default() -> Self {
+impl CardStyle {
+    pub fn new(scheme: &ColorScheme, fonts: &Fonts) -> Self {
         Self {
             height: 24,
-            padding: 16,
+            padding: PADDING,
             radius: 16,
             border_width: 1,
-            border_color: color::M3_OUTLINE,
-            background: color::M3_BACKGROUND,
-            foreground: color::M3_ON_BACKGROUND,
-            background_pressed: color::M3_SURFACE_CONTAINER,
-            foreground_pressed: color::M3_ON_SURFACE,
+            border_color: scheme.outline,
+            focused: false,
+            focus_border_width: 3,
+            focus_border_color: scheme.primary,
+            background: scheme.background,
+            foreground: scheme.on_background,
+            background_pressed: scheme.surface_container,
+            foreground_pressed: scheme.on_surface,
             animation_duration: Duration::from_millis(200),
-            font_size: font::SIZE_BODY,
+            fonts: *fonts,
+            font_size: fonts.scale.body,
         }
     }
 }
@@ -50,6 +66,11 @@ pub fn card<'a, C: 'a, OnTapFn>(
 where
     OnTapFn: Fn(&mut C) + 'a,
 {
+    let (border_width, border_color) = if style.focused {
+        (style.focus_border_width, style.focus_border_color)
+    } else {
+        (style.border_width, style.border_color)
+    };
     Button::new(on_tap, move |is_pressed: bool| {
         ZStack::new((
             RoundedRectangle::new(style.radius)
@@ -58,24 +79,28 @@ where
                 } else {
                     style.background
                 })
-                .padding(Edges::All, style.border_width)
+                .padding(Edges::All, border_width)
                 .background_color(
-                    style.border_color,
-                    RoundedRectangle::new(style.radius + style.border_width as u16),
+                    border_color,
+                    RoundedRectangle::new(style.radius + border_width as u16),
                 )
                 .scale_effect(if is_pressed { 0.9 } else { 1.0 }, UnitPoint::center())
                 .animated(Animation::ease_out(style.animation_duration), is_pressed),
-            Text::new(label, &*font::MONTSERRAT)
-                .with_font_size(style.font_size)
-                .foreground_color(if is_pressed {
-                    style.foreground_pressed
-                } else {
-                    style.foreground
-                })
-                .hint_background_color(if is_pressed {
-                    style.background_pressed
-                } else {
-                    style.background
+            style
+                .fonts
+                .text(style.fonts.regular, label, move |label, font| {
+                    Text::new(label, font)
+                        .with_font_size(style.font_size)
+                        .foreground_color(if is_pressed {
+                            style.foreground_pressed
+                        } else {
+                            style.foreground
+                        })
+                        .hint_background_color(if is_pressed {
+                            style.background_pressed
+                        } else {
+                            style.background
+                        })
                 })
                 .padding(Edges::All, style.padding)
                 .flex_frame()
@@ -1,52 +1,395 @@
-use buoyant::view::{prelude::ViewModifier, shape::RoundedRectangle, HStack, Text, VStack, View};
+use buoyant::{
+    animation::Animation,
+    view::{
+        prelude::ViewModifier,
+        shape::{Capsule, RoundedRectangle},
+        Button, ForEach, HStack, Image, Text, VStack, View,
+    },
+};
 
-use crate::view::{
-    color, font, spacing,
-    ui::button::{self, ButtonStyle},
-    AppState,
+use crate::{
+    theme::ColorScheme,
+    view::{
+        color,
+        font::Fonts,
+        framebuffer, path, spacing,
+        ui::button::{self, ButtonStyle},
+        AppState,
+    },
+    ConfirmError,
 };
 
-pub fn confirm_selection_screen(
-    data: &crate::view::AppData,
-    route_index: usize,
-) -> impl View<color::Color, AppState> + use<'_> {
-    let (route_name, route_description) = data.routes[route_index];
+/// Width and height of the robot marker on the path preview
+const MARKER_SIZE: u32 = 8;
+
+/// Checklist items shown per page of the confirmation screen
+const CHECKLIST_PAGE_ROWS: usize = 4;
+
+/// Row indices for `ForEach`, which requires a slice that outlives the view
+static CHECKLIST_ROWS: [usize; CHECKLIST_PAGE_ROWS] = [0, 1, 2, 3];
+
+/// Number of pages needed to show `len` checklist items, at least one.
+pub fn checklist_page_count(len: usize) -> usize {
+    len.div_ceil(CHECKLIST_PAGE_ROWS).max(1)
+}
+
+/// Page that the checklist item at `index` is on.
+pub fn checklist_page_of(index: usize) -> usize {
+    index / CHECKLIST_PAGE_ROWS
+}
+
+/// Padding of the card with the route's name and description
+const CARD_PADDING: u32 = 12;
 
-    VStack::new((
-        Text::new("Confirm selection", &*font::MONTSERRAT)
-            .with_font_size(font::SIZE_HEADING)
-            .foreground_color(color::M3_ON_SURFACE),
+/// Width that the checklist is assumed to take up when wrapping the route's
+/// name and description
+const CHECKLIST_WIDTH: u32 = 160;
+
+/// Width of the route's name and description, which share the screen with the
+/// path preview and the checklist if they're shown.
+fn card_text_width(preview: bool, checklist: bool) -> u32 {
+    let mut width = framebuffer::WIDTH - 2 * spacing::ELEMENT - 2 * CARD_PADDING;
+    if preview {
+        width -= path::SIZE + spacing::COMPONENT;
+    }
+    if checklist {
+        width -= CHECKLIST_WIDTH + spacing::COMPONENT;
+    }
+    width
+}
+
+/// Label of the disabled "Confirm" button when confirming is blocked.
+fn blocker_label(blocker: ConfirmError) -> &'static str {
+    match blocker {
+        ConfirmError::NotCalibrated => "Calibrate first",
+        ConfirmError::ChecklistIncomplete { .. } => "Finish checklist",
+    }
+}
+
+/// Width and height of the box of a checklist row
+const CHECK_SIZE: u32 = 14;
+
+/// Checklist row, with a box that's filled once the item is done.
+#[allow(clippy::too_many_arguments)]
+fn check_row(
+    label: String,
+    required: bool,
+    checked: bool,
+    is_pressed: bool,
+    focused: bool,
+    scheme: &ColorScheme,
+    fonts: &Fonts,
+) -> impl View<color::Color, AppState> {
+    let size = fonts.scale.caption;
+    // Optional items are dimmed since they don't block confirmation
+    let foreground = if required {
+        scheme.on_surface
+    } else {
+        scheme.on_surface_variant
+    };
+    HStack::new((
+        RoundedRectangle::new(3)
+            .foreground_color(if checked {
+                scheme.primary
+            } else {
+                scheme.outline_variant
+            })
+            .flex_frame()
+            .with_min_width(CHECK_SIZE)
+            .with_max_width(CHECK_SIZE)
+            .with_min_height(CHECK_SIZE)
+            .with_max_height(CHECK_SIZE),
+        fonts.text(fonts.regular, &label, move |label, font| {
+            Text::new(label, font)
+                .with_font_size(size)
+                .foreground_color(foreground)
+        }),
+    ))
+    .with_spacing(spacing::ELEMENT)
+    .padding(buoyant::view::prelude::Edges::All, 4)
+    .background_color(
+        if focused {
+            scheme.tertiary_container
+        } else if is_pressed {
+            scheme.surface_container_high
+        } else {
+            scheme.surface_container_highest
+        },
+        RoundedRectangle::new(8),
+    )
+}
+
+pub fn confirm_selection_screen<'a>(
+    state: &AppState,
+    data: &'a crate::view::AppData,
+) -> impl View<color::Color, AppState> + use<'a> {
+    let scheme = data.scheme();
+    let fonts = data.fonts();
+    let external = state.external.borrow();
+    let route = &data.routes[external.selection];
+    let last_run = external
+        .runs
+        .get(&external.selection)
+        .map(|run| (run.elapsed, run.elapsed > route.time_budget));
+    let path_step = state.path_step;
+    let calibration = state
+        .calibration_chip(scheme)
+        .filter(|_| state.calibration_flagged());
+    let blocker = state.confirm_blocker(data);
+    let restored = external.restored;
+    let checklist_len = state.checklist.len();
+    let checklist_page_count = checklist_page_count(checklist_len);
+    let checklist_page = state.checklist_page.min(checklist_page_count - 1);
+    let first_row = checklist_page * CHECKLIST_PAGE_ROWS;
+    let checklist = state
+        .checklist
+        .iter()
+        .enumerate()
+        .skip(first_row)
+        .take(CHECKLIST_PAGE_ROWS)
+        .map(|(index, item)| {
+            (
+                index,
+                item.label.clone(),
+                item.required,
+                item.done.is_none(),
+                state.is_checked(index),
+                state.focus == Some(index),
+            )
+        })
+        .collect::<Vec<_>>();
+    let text_width = card_text_width(route.path_preview.is_some(), checklist_len > 0);
+    // The "Confirm" button follows the checklist items in focus order
+    let confirm_focused = state.focus == Some(checklist_len);
+    // Smaller buttons leave room for the checklist
+    let (button_style, filled_button_style): (
+        fn(&ColorScheme, &Fonts) -> ButtonStyle,
+        fn(&ColorScheme, &Fonts) -> ButtonStyle,
+    ) = if checklist_len == 0 {
+        (ButtonStyle::large, ButtonStyle::filled_large)
+    } else {
+        (ButtonStyle::new, ButtonStyle::filled)
+    };
+
+    HStack::new((
+        route.path_preview.as_ref().map(|preview| {
+            // The step may belong to a different route for a frame while the
+            // selection changes
+            let path_step = if path_step < preview.points.len() {
+                path_step
+            } else {
+                0
+            };
+            let marker = preview.points[path_step];
+            Image::new(&preview.image).overlay(
+                buoyant::layout::Alignment::TopLeading,
+                // Robot marker, moving along the path from the start
+                Capsule
+                    .foreground_color(scheme.inverse_surface)
+                    .flex_frame()
+                    .with_min_width(MARKER_SIZE)
+                    .with_max_width(MARKER_SIZE)
+                    .with_min_height(MARKER_SIZE)
+                    .with_max_height(MARKER_SIZE)
+                    .padding(
+                        buoyant::view::prelude::Edges::Leading,
+                        (marker.x.clamp(0, path::SIZE as i32) as u32)
+                            .saturating_sub(MARKER_SIZE / 2),
+                    )
+                    .padding(
+                        buoyant::view::prelude::Edges::Top,
+                        (marker.y.clamp(0, path::SIZE as i32) as u32)
+                            .saturating_sub(MARKER_SIZE / 2),
+                    )
+                    .animated(
+                        Animation::linear(preview.travel_duration(path_step)),
+                        path_step,
+                    ),
+            )
+        }),
         VStack::new((
-            Text::new(route_name, &*font::MONTSERRAT)
-                .with_font_size(font::SIZE_BODY)
-                .multiline_text_alignment(buoyant::view::HorizontalTextAlignment::Center)
-                .foreground_color(color::M3_ON_SURFACE),
-            Text::new(route_description, &*font::MONTSERRAT)
-                .with_font_size(font::SIZE_CAPTION)
-                .multiline_text_alignment(buoyant::view::HorizontalTextAlignment::Center)
-                .foreground_color(color::M3_ON_SURFACE_VARIANT),
-        ))
-        .padding(buoyant::view::prelude::Edges::All, 12)
-        .flex_infinite_height(buoyant::layout::VerticalAlignment::Center)
-        .background_color(
-            color::M3_SURFACE_CONTAINER_HIGHEST,
-            RoundedRectangle::new(16),
-        ),
-        HStack::new((
-            button::button("Cancel", ButtonStyle::large(), |state: &mut AppState| {
-                state.screen = crate::view::ui::Screen::SelectCategory;
-            }),
-            button::button(
-                "Confirm",
-                ButtonStyle::filled_large(),
-                |state: &mut AppState| {
-                    state.screen = crate::view::ui::Screen::Confirmed;
-                },
-            ),
+            Text::new("Confirm selection", fonts.heading)
+                .with_font_size(fonts.scale.heading)
+                .foreground_color(scheme.on_surface),
+            VStack::new((
+                fonts.wrapped_text(
+                    fonts.regular,
+                    fonts.scale.body,
+                    text_width,
+                    route.name,
+                    move |name, font| {
+                        Text::new(name, font)
+                            .with_font_size(fonts.scale.body)
+                            .multiline_text_alignment(
+                                buoyant::view::HorizontalTextAlignment::Center,
+                            )
+                            .foreground_color(scheme.on_surface)
+                    },
+                ),
+                fonts.wrapped_text(
+                    fonts.regular,
+                    fonts.scale.caption,
+                    text_width,
+                    route.description,
+                    move |description, font| {
+                        Text::new(description, font)
+                            .with_font_size(fonts.scale.caption)
+                            .multiline_text_alignment(
+                                buoyant::view::HorizontalTextAlignment::Center,
+                            )
+                            .foreground_color(scheme.on_surface_variant)
+                    },
+                ),
+                last_run.map(|(elapsed, over_budget)| {
+                    HStack::new((
+                        Text::new(
+                            format!("Last run: {:.1} s", elapsed.as_secs_f32()),
+                            fonts.regular,
+                        )
+                        .with_font_size(fonts.scale.caption)
+                        .foreground_color(scheme.on_surface_variant),
+                        // Warn about routes that are too slow to finish in time
+                        over_budget.then(|| {
+                            Text::new(
+                                format!("Over {} s budget", route.time_budget.as_secs()),
+                                fonts.regular,
+                            )
+                            .with_font_size(fonts.scale.caption)
+                            .foreground_color(scheme.on_error_container)
+                            .hint_background_color(scheme.error_container)
+                            .padding(buoyant::view::prelude::Edges::Horizontal, spacing::ELEMENT)
+                            .padding(buoyant::view::prelude::Edges::Vertical, 2)
+                            .background_color(scheme.error_container, Capsule)
+                        }),
+                    ))
+                    .with_spacing(spacing::ELEMENT)
+                }),
+                // Flag a robot that isn't ready before it's too late to fix
+                calibration.map(|(label, background, foreground)| {
+                    Text::new(label, fonts.regular)
+                        .with_font_size(fonts.scale.caption)
+                        .foreground_color(foreground)
+                        .hint_background_color(background)
+                        .padding(buoyant::view::prelude::Edges::Horizontal, spacing::ELEMENT)
+                        .padding(buoyant::view::prelude::Edges::Vertical, 2)
+                        .background_color(background, Capsule)
+                }),
+                // A restored selection that couldn't be confirmed again at
+                // startup waits here for the user
+                restored.then(|| {
+                    Text::new("Restored after reboot", fonts.regular)
+                        .with_font_size(fonts.scale.caption)
+                        .foreground_color(scheme.on_secondary_container)
+                        .hint_background_color(scheme.secondary_container)
+                        .padding(buoyant::view::prelude::Edges::Horizontal, spacing::ELEMENT)
+                        .padding(buoyant::view::prelude::Edges::Vertical, 2)
+                        .background_color(scheme.secondary_container, Capsule)
+                }),
+            ))
+            .with_spacing(spacing::LIST_ITEM)
+            .padding(buoyant::view::prelude::Edges::All, CARD_PADDING)
+            .flex_infinite_height(buoyant::layout::VerticalAlignment::Center)
+            .background_color(scheme.surface_container_highest, RoundedRectangle::new(16)),
+            HStack::new((
+                button::button(
+                    "Cancel",
+                    button_style(scheme, fonts),
+                    |state: &mut AppState| {
+                        state.navigate(crate::view::ui::Screen::SelectCategory);
+                    },
+                ),
+                button::button(
+                    if state.is_favorite() { "Unpin" } else { "Pin" },
+                    button_style(scheme, fonts),
+                    move |state: &mut AppState| {
+                        state.toggle_favorite(data);
+                    },
+                ),
+                button::button(
+                    blocker.map_or("Confirm", blocker_label),
+                    ButtonStyle {
+                        focused: confirm_focused,
+                        ..match blocker {
+                            Some(_) => button_style(scheme, fonts).disabled(scheme),
+                            None => filled_button_style(scheme, fonts),
+                        }
+                    },
+                    move |state: &mut AppState| {
+                        state.confirm(data);
+                    },
+                ),
+            ))
+            .with_spacing(spacing::ELEMENT),
         ))
-        .with_spacing(spacing::ELEMENT),
+        .with_spacing(8),
+        (!checklist.is_empty()).then(|| {
+            let len = checklist.len();
+            VStack::new((
+                HStack::new((
+                    Text::new("Checklist", fonts.heading)
+                        .with_font_size(fonts.scale.body)
+                        .foreground_color(scheme.on_surface),
+                    // Long checklists are paged so every required item can
+                    // be reached
+                    (checklist_page_count > 1).then(|| {
+                        HStack::new((
+                            button::button(
+                                "Prev",
+                                ButtonStyle::new(scheme, fonts),
+                                move |state: &mut AppState| {
+                                    state.checklist_page = checklist_page.saturating_sub(1);
+                                },
+                            ),
+                            Text::new(
+                                format!("{}/{}", checklist_page + 1, checklist_page_count),
+                                fonts.regular,
+                            )
+                            .with_font_size(fonts.scale.caption)
+                            .foreground_color(scheme.on_surface),
+                            button::button(
+                                "Next",
+                                ButtonStyle::new(scheme, fonts),
+                                move |state: &mut AppState| {
+                                    state.checklist_page =
+                                        (checklist_page + 1).min(checklist_page_count - 1);
+                                },
+                            ),
+                        ))
+                        .with_spacing(spacing::ELEMENT)
+                    }),
+                ))
+                .with_spacing(spacing::ELEMENT),
+                ForEach::<CHECKLIST_PAGE_ROWS>::new_vertical(
+                    &CHECKLIST_ROWS[..len],
+                    move |row: &usize| {
+                        let (index, label, required, manual, checked, focused) =
+                            checklist[*row].clone();
+                        Button::new(
+                            move |state: &mut AppState| {
+                                state.toggle_check(index);
+                            },
+                            move |is_pressed: bool| {
+                                check_row(
+                                    label.clone(),
+                                    required,
+                                    checked,
+                                    // Items checked by the robot can't be tapped
+                                    is_pressed && manual,
+                                    focused,
+                                    scheme,
+                                    fonts,
+                                )
+                            },
+                        )
+                    },
+                )
+                .with_spacing(spacing::LIST_ITEM),
+            ))
+            .with_spacing(spacing::ELEMENT)
+            .with_alignment(buoyant::layout::HorizontalAlignment::Leading)
+        }),
     ))
-    .with_spacing(8)
+    .with_spacing(spacing::COMPONENT)
     .padding(buoyant::view::prelude::Edges::All, spacing::ELEMENT)
     .flex_frame()
     .with_alignment(buoyant::layout::Alignment::Center)
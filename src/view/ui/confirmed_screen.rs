@@ -0,0 +1,185 @@
+use buoyant::view::prelude::*;
+use vexide::competition::CompetitionMode;
+
+use crate::{
+    route::Alliance,
+    view::{
+        color,
+        font::Fonts,
+        framebuffer, spacing,
+        ui::{AppData, AppState},
+    },
+};
+
+/// Width of text in a card, inside the screen's margin and the card's padding
+const CARD_TEXT_WIDTH: u32 = framebuffer::WIDTH - 4 * spacing::SECTION_MARGIN;
+
+/// Small capsule with a line of text, for the facts about the confirmed route.
+fn chip(
+    label: String,
+    background: color::Color,
+    foreground: color::Color,
+    fonts: &Fonts,
+) -> impl View<color::Color, AppState> {
+    let size = fonts.scale.caption;
+    fonts
+        .text(fonts.regular, &label, move |label, font| {
+            Text::new(label, font)
+                .with_font_size(size)
+                .foreground_color(foreground)
+                .hint_background_color(background)
+        })
+        .padding(Edges::Horizontal, spacing::ELEMENT)
+        .padding(Edges::Vertical, 2)
+        .background_color(background, Capsule)
+}
+
+pub fn confirmed_screen<'a>(
+    state: &AppState,
+    data: &'a AppData,
+) -> impl View<color::Color, AppState> + use<'a> {
+    let scheme = data.scheme();
+    let fonts = data.fonts();
+    let external = state.external.borrow();
+    let restored = external.restored;
+    let route = &data.routes[external.selection];
+    let category = data.category_names[route.category_index].1.as_str();
+    let start = match (route.alliance, route.side) {
+        (Some(alliance), Some(side)) => Some((Some(alliance), format!("{alliance}, {side}"))),
+        (Some(alliance), None) => Some((Some(alliance), alliance.to_string())),
+        (None, Some(side)) => Some((None, side.to_string())),
+        (None, None) => None,
+    };
+    let calibration = state.calibration_chip(scheme);
+    let clock = state
+        .confirmed_at
+        .is_some()
+        .then(|| state.confirmed_clock.clone());
+    let status = match state.mode {
+        CompetitionMode::Autonomous => "Running autonomous".to_string(),
+        CompetitionMode::Driver => "Driver control".to_string(),
+        // The last run of another route says nothing about the confirmed one
+        CompetitionMode::Disabled => match external.runs.get(&external.selection) {
+            Some(run) => format!("Autonomous done in {:.1} s", run.elapsed.as_secs_f32()),
+            None => "Waiting for autonomous".to_string(),
+        },
+    };
+    // Only report failures once autonomous is over so the report doesn't
+    // cost render time during the match. Like the status, this is about the
+    // confirmed route, not whichever route ran last.
+    let failure = external
+        .runs
+        .get(&external.selection)
+        .filter(|_| state.mode != CompetitionMode::Autonomous)
+        .and_then(|run| {
+            run.error
+                .clone()
+                .map(|error| (data.routes[external.selection].name, run.elapsed, error))
+        });
+
+    VStack::new((
+        // Summary that can be read from behind the field
+        VStack::new((
+            fonts.text(fonts.regular, category, move |category, font| {
+                Text::new(category, font)
+                    .with_font_size(fonts.scale.caption)
+                    .foreground_color(scheme.on_surface_variant)
+            }),
+            fonts.wrapped_text(
+                fonts.heading,
+                fonts.scale.heading * 3 / 2,
+                CARD_TEXT_WIDTH,
+                route.name,
+                move |name, font| {
+                    Text::new(name, font)
+                        .with_font_size(fonts.scale.heading * 3 / 2)
+                        .multiline_text_alignment(buoyant::view::HorizontalTextAlignment::Center)
+                        .foreground_color(scheme.on_surface)
+                },
+            ),
+            HStack::new((
+                start.map(|(alliance, label)| match alliance {
+                    Some(Alliance::Red) => chip(
+                        label,
+                        scheme.error_container,
+                        scheme.on_error_container,
+                        fonts,
+                    ),
+                    Some(Alliance::Blue) => chip(
+                        label,
+                        scheme.primary_container,
+                        scheme.on_primary_container,
+                        fonts,
+                    ),
+                    None => chip(
+                        label,
+                        scheme.secondary_container,
+                        scheme.on_secondary_container,
+                        fonts,
+                    ),
+                }),
+                calibration.map(|(label, background, foreground)| {
+                    chip(label.to_string(), background, foreground, fonts)
+                }),
+                clock.map(|clock| {
+                    chip(
+                        clock,
+                        scheme.secondary_container,
+                        scheme.on_secondary_container,
+                        fonts,
+                    )
+                }),
+            ))
+            .with_spacing(spacing::ELEMENT),
+            Text::new(status, fonts.regular)
+                .with_font_size(fonts.scale.body)
+                .foreground_color(scheme.on_surface),
+        ))
+        .with_spacing(spacing::LIST_ITEM)
+        .padding(Edges::All, spacing::SECTION_MARGIN)
+        .background_color(scheme.surface_container_highest, RoundedRectangle::new(16)),
+        Spacer::default(),
+        failure.map(|(route_name, elapsed, error)| {
+            VStack::new((
+                fonts.wrapped_text(
+                    fonts.regular,
+                    fonts.scale.body,
+                    CARD_TEXT_WIDTH,
+                    &format!("{} failed after {:.1} s", route_name, elapsed.as_secs_f32()),
+                    move |label, font| {
+                        Text::new(label, font)
+                            .with_font_size(fonts.scale.body)
+                            .multiline_text_alignment(
+                                buoyant::view::HorizontalTextAlignment::Center,
+                            )
+                            .foreground_color(scheme.on_error_container)
+                            .hint_background_color(scheme.error_container)
+                    },
+                ),
+                Text::new(error, fonts.regular)
+                    .with_font_size(fonts.scale.caption)
+                    .multiline_text_alignment(buoyant::view::HorizontalTextAlignment::Center)
+                    .foreground_color(scheme.on_error_container)
+                    .hint_background_color(scheme.error_container),
+            ))
+            .with_spacing(spacing::ELEMENT)
+            .padding(Edges::All, spacing::SECTION_MARGIN)
+            .background_color(scheme.error_container, RoundedRectangle::new(16))
+        }),
+        // Let the drive team know that the selection survived a reboot
+        restored.then(|| {
+            Text::new("Selection restored after reboot", fonts.regular)
+                .with_font_size(fonts.scale.caption)
+                .foreground_color(scheme.on_secondary_container)
+                .hint_background_color(scheme.secondary_container)
+                .padding(Edges::Horizontal, spacing::ELEMENT * 2)
+                .padding(Edges::Vertical, spacing::ELEMENT)
+                .background_color(scheme.secondary_container, Capsule)
+        }),
+    ))
+    .with_spacing(spacing::COMPONENT)
+    .padding(Edges::All, spacing::SECTION_MARGIN)
+    .flex_frame()
+    .with_infinite_max_height()
+    .with_infinite_max_width()
+}
@@ -1,89 +1,460 @@
-use buoyant::view::{
-    prelude::ViewModifier, scroll_view::ScrollDirection, shape::RoundedRectangle, ForEach, HStack,
-    ScrollView, Spacer, Text, VStack, View,
+use buoyant::{
+    match_view,
+    view::{
+        prelude::ViewModifier,
+        shape::{Capsule, Rectangle, RoundedRectangle},
+        Button, ForEach, HStack, Spacer, Text, VStack, View,
+    },
 };
 
-use crate::view::{
-    color, font, spacing,
-    ui::button::{self, ButtonStyle},
-    AppState,
+use crate::{
+    diagnostics::{DiagnosticHistory, DiagnosticKey, HISTORY_LEN},
+    theme::ColorScheme,
+    view::{
+        color,
+        font::Fonts,
+        spacing,
+        ui::{
+            button::{self, ButtonStyle},
+            AppData,
+        },
+        AppState,
+    },
+    DiagnosticEntry, DiagnosticStatus,
 };
 
-// This screen is very inefficient and bleeds memory, but it should be okay since
-// diagnostics mode is only intended for short-term use not during a match.
-//
-// This is because we must return a static lifetime from the function, but the
-// diagnostics data is dynamic.
+/// Rows on one page of the diagnostics screen
+const PAGE_ROWS: usize = 3;
+/// Rows on one page of the diagnostics screen in the compact layout
+const COMPACT_PAGE_ROWS: usize = 6;
 
-pub fn diagnostics_screen(state: &crate::view::AppState) -> impl View<color::Color, AppState> {
-    let diagnostics = state
-        .diagnostics
-        .clone()
-        .expect("diagnostics should be Some when in diagnostics screen");
-    assert!(
-        diagnostics.len() <= 16,
-        "diagnostics data exceeds maximum of 16 entries"
-    );
+/// Width of the gauge bar of entries with a range
+const GAUGE_WIDTH: u32 = 64;
+/// Height of the gauge bar of entries with a range
+const GAUGE_HEIGHT: u32 = 6;
+
+/// Number of recent samples in an entry's sparkline
+const SPARKLINE_SAMPLES: usize = 24;
+/// Width of each bar of a sparkline
+const SPARKLINE_BAR_WIDTH: u32 = 2;
+/// Height of a sparkline
+const SPARKLINE_HEIGHT: u32 = 14;
+
+/// Width of each bar of the full-screen chart
+const CHART_BAR_WIDTH: u32 = 6;
+/// Height of the full-screen chart
+const CHART_HEIGHT: u32 = 96;
+
+/// Row and sample indices for `ForEach`, which requires a slice that outlives
+/// the view
+static INDICES: [usize; HISTORY_LEN] = {
+    let mut samples = [0; HISTORY_LEN];
+    let mut i = 0;
+    while i < HISTORY_LEN {
+        samples[i] = i;
+        i += 1;
+    }
+    samples
+};
+
+/// A line on the diagnostics screen.
+///
+/// Rows don't include the entries' history, so comparing them only picks up
+/// changes to the entries themselves.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Row {
+    /// Heading for the entries below it
+    Section(String),
+    /// An entry
+    Entry(DiagnosticEntry),
+}
+
+/// Lays out the entries, with a heading wherever the section changes.
+fn rows(entries: &[DiagnosticEntry]) -> Vec<Row> {
+    let mut rows = Vec::with_capacity(entries.len());
+    let mut section = None;
+    for entry in entries {
+        if entry.section.is_some() && entry.section != section {
+            section = entry.section.clone();
+            rows.push(Row::Section(entry.section.clone().unwrap_or_default()));
+        }
+        rows.push(Row::Entry(entry.clone()));
+    }
+    rows
+}
+
+/// History of an entry, if it's numeric.
+fn samples(entry: &DiagnosticEntry, history: &DiagnosticHistory) -> Option<Vec<f64>> {
+    history
+        .get(entry)
+        .map(|samples| samples.iter().copied().collect())
+}
+
+fn page_rows(compact: bool) -> usize {
+    if compact {
+        COMPACT_PAGE_ROWS
+    } else {
+        PAGE_ROWS
+    }
+}
+
+/// Number of pages needed to show the entries.
+pub fn page_count(entries: &[DiagnosticEntry], compact: bool) -> usize {
+    rows(entries).len().div_ceil(page_rows(compact)).max(1)
+}
+
+/// Rows shown on the given page, clamped to the last page.
+pub fn page(entries: &[DiagnosticEntry], compact: bool, page: usize) -> Vec<Row> {
+    let per_page = page_rows(compact);
+    let page = page.min(page_count(entries, compact) - 1);
+    rows(entries)
+        .into_iter()
+        .skip(page * per_page)
+        .take(per_page)
+        .collect()
+}
+
+/// The entry shown in the full-screen chart, if it still exists.
+fn chart_entry<'a>(
+    entries: &'a [DiagnosticEntry],
+    key: &DiagnosticKey,
+) -> Option<&'a DiagnosticEntry> {
+    entries.iter().find(|entry| entry.key() == *key)
+}
+
+/// Row of the entry shown in the full-screen chart, if it still exists.
+pub fn chart(entries: &[DiagnosticEntry], key: &DiagnosticKey) -> Option<Row> {
+    chart_entry(entries, key).cloned().map(Row::Entry)
+}
+
+/// Background, text and gauge colors of an entry's row.
+fn status_colors(
+    status: DiagnosticStatus,
+    scheme: &ColorScheme,
+) -> (color::Color, color::Color, color::Color) {
+    match status {
+        DiagnosticStatus::Ok => (
+            scheme.surface_container_highest,
+            scheme.on_surface,
+            scheme.primary,
+        ),
+        DiagnosticStatus::Warning => (
+            scheme.tertiary_container,
+            scheme.on_tertiary_container,
+            scheme.tertiary,
+        ),
+        DiagnosticStatus::Error => (
+            scheme.error_container,
+            scheme.on_error_container,
+            scheme.error,
+        ),
+    }
+}
+
+/// Smallest and largest of the samples.
+fn min_max(samples: &[f64]) -> (f64, f64) {
+    samples
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &sample| {
+            (min.min(sample), max.max(sample))
+        })
+}
+
+/// Bar chart of the last `count` samples, scaled between their minimum and
+/// maximum so that small changes still show.
+fn bars(
+    samples: &[f64],
+    count: usize,
+    bar_width: u32,
+    height: u32,
+    color: color::Color,
+) -> impl View<color::Color, AppState> {
+    let samples = &samples[samples.len().saturating_sub(count)..];
+    let (min, max) = min_max(samples);
+    let heights = samples
+        .iter()
+        .map(|&sample| {
+            let fraction = if max > min {
+                (sample - min) / (max - min)
+            } else {
+                0.5
+            };
+            ((height as f64 * fraction) as u32).max(1)
+        })
+        .collect::<Vec<_>>();
 
-    let len = diagnostics.len();
+    ForEach::<HISTORY_LEN>::new_horizontal(&INDICES[..heights.len()], move |i| {
+        Rectangle
+            .foreground_color(color)
+            .flex_frame()
+            .with_min_width(bar_width)
+            .with_max_width(bar_width)
+            .with_min_height(heights[*i])
+            .with_max_height(heights[*i])
+            .flex_frame()
+            .with_min_height(height)
+            .with_max_height(height)
+            .with_alignment(buoyant::layout::Alignment::Bottom)
+    })
+    .with_spacing(1)
+}
+
+/// A diagnostic entry's row, with a sparkline if it has history.
+fn entry_row(
+    entry: DiagnosticEntry,
+    samples: Option<Vec<f64>>,
+    is_pressed: bool,
+    compact: bool,
+    scheme: ColorScheme,
+    fonts: Fonts,
+) -> impl View<color::Color, AppState> {
+    let (background, foreground, gauge_color) = status_colors(entry.status, &scheme);
+    let font_size = if compact {
+        fonts.scale.caption
+    } else {
+        fonts.scale.body
+    };
+    let value = entry.formatted_value();
+    HStack::new((
+        fonts.text(fonts.regular, &entry.label, move |label, font| {
+            Text::new(label, font)
+                .with_font_size(font_size)
+                .foreground_color(foreground)
+        }),
+        Spacer::default(),
+        samples.map(|samples| {
+            bars(
+                &samples,
+                SPARKLINE_SAMPLES,
+                SPARKLINE_BAR_WIDTH,
+                SPARKLINE_HEIGHT,
+                gauge_color,
+            )
+        }),
+        entry.gauge().map(|fraction| {
+            let filled = (GAUGE_WIDTH as f32 * fraction) as u32;
+            Capsule
+                .foreground_color(scheme.outline_variant)
+                .flex_frame()
+                .with_min_width(GAUGE_WIDTH)
+                .with_max_width(GAUGE_WIDTH)
+                .with_min_height(GAUGE_HEIGHT)
+                .with_max_height(GAUGE_HEIGHT)
+                .overlay(
+                    buoyant::layout::Alignment::Leading,
+                    Capsule
+                        .foreground_color(gauge_color)
+                        .flex_frame()
+                        .with_min_width(filled)
+                        .with_max_width(filled)
+                        .with_min_height(GAUGE_HEIGHT)
+                        .with_max_height(GAUGE_HEIGHT),
+                )
+        }),
+        fonts.text(fonts.monospace, &value, move |value, font| {
+            Text::new(value, font)
+                .with_font_size(font_size)
+                .foreground_color(foreground)
+        }),
+    ))
+    .with_spacing(spacing::ELEMENT)
+    .padding(
+        buoyant::view::prelude::Edges::All,
+        if compact { 4 } else { spacing::ELEMENT },
+    )
+    .background_color(
+        if is_pressed {
+            scheme.outline_variant
+        } else {
+            background
+        },
+        RoundedRectangle::new(if compact { 8 } else { 12 }),
+    )
+}
+
+// The diagnostics are cloned into the view since it must have a static
+// lifetime, but only one page of them is built at a time.
+
+fn list(
+    state: &AppState,
+    entries: &[DiagnosticEntry],
+    live: bool,
+    scheme: ColorScheme,
+    fonts: Fonts,
+) -> impl View<color::Color, AppState> {
     let compact = state.interface.diagnostics_compact();
+    let page_count = page_count(entries, compact);
+    let page = state.page.min(page_count - 1);
+    let rows = self::page(entries, compact, page)
+        .into_iter()
+        .map(|row| {
+            let samples = match &row {
+                Row::Entry(entry) => samples(entry, &state.diagnostic_history),
+                Row::Section(_) => None,
+            };
+            (row, samples)
+        })
+        .collect::<Vec<_>>();
+    let len = rows.len();
 
-    ScrollView::new(
-        VStack::new((
-            HStack::new((
-                Text::new("Diagnostics", &*font::MONTSERRAT)
-                    .with_font_size(font::SIZE_HEADING)
-                    .foreground_color(color::M3_ON_SURFACE),
-                Spacer::default(),
-                button::button("Refresh", ButtonStyle::default(), |state: &mut AppState| {
-                    state.refresh_diagnostics();
-                }),
-            ))
-            .with_spacing(spacing::ELEMENT),
-            ForEach::<16>::new_vertical(
-                // ForEach requires a static lifetime for items
-                &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15][0..len],
-                move |i| {
-                    let (key, value) = diagnostics[*i as usize].clone();
-                    HStack::new((
-                        Text::new(key, &*font::MONTSERRAT)
-                            .with_font_size(if compact {
-                                font::SIZE_CAPTION
-                            } else {
-                                font::SIZE_BODY
-                            })
-                            .foreground_color(color::M3_ON_SURFACE),
-                        Spacer::default(),
-                        Text::new(value, &*font::MONTSERRAT)
-                            .with_font_size(if compact {
-                                font::SIZE_CAPTION
-                            } else {
-                                font::SIZE_BODY
-                            })
-                            .foreground_color(color::M3_ON_SURFACE_VARIANT),
-                    ))
-                    .padding(
-                        buoyant::view::prelude::Edges::All,
-                        if compact { 4 } else { spacing::ELEMENT },
-                    )
-                    .background_color(
-                        color::M3_SURFACE_CONTAINER_HIGHEST,
-                        RoundedRectangle::new(if compact { 8 } else { 12 }),
+    VStack::new((
+        HStack::new((
+            Text::new("Diagnostics", fonts.heading)
+                .with_font_size(fonts.scale.heading)
+                .foreground_color(scheme.on_surface),
+            Spacer::default(),
+            (page_count > 1).then(|| {
+                HStack::new((
+                    button::button(
+                        "Prev",
+                        ButtonStyle::new(&scheme, &fonts),
+                        move |state: &mut AppState| {
+                            state.page = page.saturating_sub(1);
+                        },
+                    ),
+                    Text::new(format!("{}/{}", page + 1, page_count), fonts.regular)
+                        .with_font_size(fonts.scale.caption)
+                        .foreground_color(scheme.on_surface),
+                    button::button(
+                        "Next",
+                        ButtonStyle::new(&scheme, &fonts),
+                        move |state: &mut AppState| {
+                            state.page = (page + 1).min(page_count - 1);
+                        },
+                    ),
+                ))
+                .with_spacing(spacing::ELEMENT)
+            }),
+            match_view!(live, {
+                true => Text::new("Live", fonts.regular)
+                    .with_font_size(fonts.scale.caption)
+                    .foreground_color(scheme.on_primary_container)
+                    .padding(buoyant::view::prelude::Edges::Horizontal, spacing::ELEMENT)
+                    .padding(buoyant::view::prelude::Edges::Vertical, 2)
+                    .background_color(scheme.primary_container, Capsule),
+                false => button::button(
+                    "Refresh",
+                    ButtonStyle::new(&scheme, &fonts),
+                    |state: &mut AppState| {
+                        state.refresh_diagnostics();
+                    },
+                ),
+            }),
+        ))
+        .with_spacing(spacing::ELEMENT),
+        ForEach::<COMPACT_PAGE_ROWS>::new_vertical(&INDICES[..len], move |i| {
+            match_view!(rows[*i].clone(), {
+                (Row::Section(title), _) => fonts
+                    .text(fonts.heading, &title, move |title, font| {
+                        Text::new(title, font)
+                            .with_font_size(fonts.scale.caption)
+                            .foreground_color(scheme.primary)
+                    })
+                    .padding(buoyant::view::prelude::Edges::Horizontal, spacing::ELEMENT),
+                // Numeric entries open a chart of their history when tapped
+                (Row::Entry(entry), Some(samples)) => {
+                    let key = entry.key();
+                    Button::new(
+                        move |state: &mut AppState| {
+                            state.open_chart(key.clone());
+                        },
+                        move |is_pressed: bool| {
+                            entry_row(
+                                entry.clone(),
+                                Some(samples.clone()),
+                                is_pressed,
+                                compact,
+                                scheme,
+                                fonts,
+                            )
+                        },
                     )
                 },
-            )
-            .with_spacing(if compact { 2 } else { spacing::LIST_ITEM }),
+                (Row::Entry(entry), None) => {
+                    entry_row(entry, None, false, compact, scheme, fonts)
+                },
+            })
+        })
+        .with_spacing(if compact { 2 } else { spacing::LIST_ITEM }),
+    ))
+    .with_spacing(spacing::ELEMENT)
+}
+
+/// Full-screen chart of an entry's history.
+fn chart_view(
+    entry: DiagnosticEntry,
+    samples: Vec<f64>,
+    scheme: ColorScheme,
+    fonts: Fonts,
+) -> impl View<color::Color, AppState> {
+    let (min, max) = min_max(&samples);
+    let average = samples.iter().sum::<f64>() / samples.len().max(1) as f64;
+    let (_, _, color) = status_colors(entry.status, &scheme);
+    let stat = move |label: &'static str, value: String| {
+        VStack::new((
+            Text::new(label, fonts.regular)
+                .with_font_size(fonts.scale.caption)
+                .foreground_color(scheme.on_surface_variant),
+            fonts.text(fonts.monospace, &value, move |value, font| {
+                Text::new(value, font)
+                    .with_font_size(fonts.scale.body)
+                    .foreground_color(scheme.on_surface)
+            }),
         ))
-        .with_spacing(spacing::ELEMENT)
-        .padding(buoyant::view::prelude::Edges::All, spacing::SECTION_MARGIN)
-        .flex_frame()
-        .with_alignment(buoyant::layout::Alignment::Center),
-    )
-    .with_direction(ScrollDirection::Vertical)
-    .with_overlapping_bar(true)
-    .with_bar_visibility(if len > if compact { 5 } else { 3 } {
-        buoyant::view::scroll_view::ScrollBarVisibility::Always
-    } else {
-        buoyant::view::scroll_view::ScrollBarVisibility::Never
+        .with_spacing(2)
+    };
+
+    VStack::new((
+        HStack::new((
+            fonts.text(fonts.heading, &entry.label, move |label, font| {
+                Text::new(label, font)
+                    .with_font_size(fonts.scale.heading)
+                    .foreground_color(scheme.on_surface)
+            }),
+            Spacer::default(),
+            button::button(
+                "Back",
+                ButtonStyle::new(&scheme, &fonts),
+                |state: &mut AppState| {
+                    state.close_chart();
+                },
+            ),
+        ))
+        .with_spacing(spacing::ELEMENT),
+        bars(&samples, HISTORY_LEN, CHART_BAR_WIDTH, CHART_HEIGHT, color)
+            .padding(buoyant::view::prelude::Edges::All, spacing::ELEMENT)
+            .background_color(scheme.surface_container_highest, RoundedRectangle::new(12)),
+        HStack::new((
+            stat("Min", entry.format_number(min)),
+            Spacer::default(),
+            stat("Average", entry.format_number(average)),
+            Spacer::default(),
+            stat("Max", entry.format_number(max)),
+            Spacer::default(),
+            stat("Now", entry.formatted_value()),
+        )),
+    ))
+    .with_spacing(spacing::ELEMENT)
+}
+
+pub fn diagnostics_screen(state: &AppState, data: &AppData) -> impl View<color::Color, AppState> {
+    let scheme = *data.scheme();
+    let fonts = *data.fonts();
+    let entries = state
+        .diagnostics
+        .as_deref()
+        .expect("diagnostics should be Some when in diagnostics screen");
+    let chart = state
+        .chart
+        .as_ref()
+        .and_then(|key| chart_entry(entries, key))
+        .and_then(|entry| Some((entry.clone(), samples(entry, &state.diagnostic_history)?)));
+
+    match_view!(chart, {
+        Some((entry, samples)) => chart_view(entry, samples, scheme, fonts),
+        _ => list(state, entries, state.diagnostics_live(data), scheme, fonts),
     })
+    .padding(buoyant::view::prelude::Edges::All, spacing::SECTION_MARGIN)
+    .flex_frame()
+    .with_alignment(buoyant::layout::Alignment::Top)
 }
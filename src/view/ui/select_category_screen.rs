@@ -1,15 +1,64 @@
-use buoyant::view::View;
+use buoyant::view::prelude::*;
 
-use crate::view::{color, AppState};
+use crate::view::{
+    color, spacing,
+    ui::button::{self, ButtonStyle},
+    AppData, AppState,
+};
 
-pub fn select_category_screen(
-    data: &crate::view::AppData,
-) -> impl View<color::Color, AppState> + use<'_> {
-    super::selector::selector(
-        "Select category",
-        &data.category_names,
-        move |state: &mut AppState, category_index: usize| {
-            state.screen = crate::view::ui::Screen::SelectRoute(category_index);
-        },
-    )
+pub fn select_category_screen<'a>(
+    state: &AppState,
+    data: &'a AppData,
+) -> impl View<color::Color, AppState> + use<'a> {
+    let shortcuts = state.shortcuts();
+    let (items, layout) = state
+        .grid(data)
+        .expect("category screen should have a grid");
+    let favorite_count = state.favorites.len();
+
+    VStack::new((
+        (!shortcuts.is_empty()).then(|| {
+            // Favorites and recent routes jump straight to confirmation.
+            // There's room for at most four, so each slot is spelled out.
+            let shortcut = |slot: usize| {
+                shortcuts.get(slot).map(|&route| {
+                    button::button(
+                        data.routes[route].name,
+                        if slot < favorite_count {
+                            ButtonStyle::filled(data.scheme(), data.fonts())
+                        } else {
+                            ButtonStyle::new(data.scheme(), data.fonts())
+                        },
+                        move |state: &mut AppState| state.select_route(route),
+                    )
+                })
+            };
+            HStack::new((
+                shortcut(0),
+                shortcut(1),
+                shortcut(2),
+                shortcut(3),
+                Spacer::default(),
+            ))
+            .with_spacing(spacing::ELEMENT)
+            .padding(Edges::Horizontal, spacing::SECTION_MARGIN)
+            .padding(Edges::Top, spacing::SECTION_MARGIN)
+        }),
+        super::selector::selector(
+            "Select category",
+            items,
+            layout,
+            state.page,
+            state.focus,
+            data.config.card_height,
+            data.scheme(),
+            data.fonts(),
+            move |state: &mut AppState, category_index: usize| {
+                state.navigate(crate::view::ui::Screen::SelectRoute(category_index));
+            },
+            move |state: &mut AppState, page: usize| {
+                state.set_page(page, layout);
+            },
+        ),
+    ))
 }
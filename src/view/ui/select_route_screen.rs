@@ -6,17 +6,23 @@ use crate::view::{
     AppState,
 };
 
-pub fn select_route_screen(
-    data: &crate::view::AppData,
+pub fn select_route_screen<'a>(
+    state: &AppState,
+    data: &'a crate::view::AppData,
     category_index: usize,
-) -> impl View<color::Color, AppState> + use<'_> {
+) -> impl View<color::Color, AppState> + use<'a> {
     let routes = &data.route_names_map[&category_index];
+    let (_, layout) = state.grid(data).expect("route screen should have a grid");
+    let scheme = data.scheme();
+    let fonts = data.fonts();
 
     ZStack::new((
         VStack::new((
-            button::button("Back", ButtonStyle::default(), |state: &mut AppState| {
-                state.screen = crate::view::ui::Screen::SelectCategory
-            })
+            button::button(
+                "Back",
+                ButtonStyle::new(scheme, fonts),
+                |state: &mut AppState| state.navigate(crate::view::ui::Screen::SelectCategory),
+            )
             .padding(buoyant::view::prelude::Edges::All, 8),
             EmptyView,
         ))
@@ -27,10 +33,17 @@ pub fn select_route_screen(
         super::selector::selector(
             "Select route",
             routes,
+            layout,
+            state.page,
+            state.focus,
+            data.config.card_height,
+            scheme,
+            fonts,
             move |state: &mut AppState, route_index: usize| {
-                let global_route_index = routes[route_index].2;
-                state.external.borrow_mut().selection = global_route_index;
-                state.screen = crate::view::ui::Screen::ConfirmSelection;
+                state.select_route(routes[route_index].2);
+            },
+            move |state: &mut AppState, page: usize| {
+                state.set_page(page, layout);
             },
         ),
     ))
@@ -1,78 +1,318 @@
-use buoyant::{
-    match_view,
-    view::{prelude::*, scroll_view::ScrollDirection},
+use buoyant::{match_view, view::prelude::*};
+
+use crate::{
+    theme::ColorScheme,
+    view::{
+        color,
+        font::Fonts,
+        framebuffer, spacing,
+        ui::{
+            button::{self, ButtonStyle},
+            card::{self, CardStyle},
+        },
+    },
+    MAX_GRID_COLUMNS,
 };
-use embedded_graphics::prelude::RgbColor as _;
 
-use crate::view::{color, font, spacing};
+/// Most rows shown on one page of the selector grid
+const MAX_PAGE_ROWS: usize = 8;
+
+/// Row indices for `ForEach`, which requires a slice that outlives the view
+static ROWS: [usize; MAX_PAGE_ROWS] = [0, 1, 2, 3, 4, 5, 6, 7];
+
+/// Height left for the grid's rows on a screen with only a title and the
+/// bottom bar
+pub const GRID_HEIGHT: u32 = 124;
+
+/// Width of the grid, inside the screen's margins
+const GRID_WIDTH: u32 = framebuffer::WIDTH - 2 * spacing::SECTION_MARGIN;
+
+/// Most columns whose cards leave room for a label `label_width` pixels wide.
+fn columns_to_fit(label_width: f32) -> usize {
+    (1..=MAX_GRID_COLUMNS)
+        .rev()
+        .find(|&columns| {
+            let columns = columns as u32;
+            let card_width = (GRID_WIDTH - (columns - 1) * spacing::COMPONENT) / columns;
+            label_width <= card_width.saturating_sub(2 * card::PADDING) as f32
+        })
+        .unwrap_or(1)
+}
+
+/// Columns and rows of the selector grid.
+///
+/// Only one page of rows is built at a time, so the number of items is
+/// unlimited and doesn't affect memory use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridLayout {
+    pub columns: usize,
+    pub rows_per_page: usize,
+}
+
+impl GridLayout {
+    /// Lays out the items in rows of `card_height` that fit in `height`.
+    ///
+    /// If `columns` is `None`, it's chosen so that the widest label, measured
+    /// in the cards' font, fits.
+    pub fn new(
+        items: &[(usize, String, usize)],
+        columns: Option<usize>,
+        card_height: u32,
+        height: u32,
+        fonts: &Fonts,
+    ) -> Self {
+        let columns = columns
+            .unwrap_or_else(|| {
+                let widest = items
+                    .iter()
+                    .map(|(_, name, _)| fonts.measure(fonts.regular, fonts.scale.body, name))
+                    .fold(0.0, f32::max);
+                columns_to_fit(widest)
+            })
+            .clamp(1, MAX_GRID_COLUMNS);
+        let rows_per_page = ((height + spacing::COMPONENT) / (card_height + spacing::COMPONENT))
+            .clamp(1, MAX_PAGE_ROWS as u32) as usize;
+        Self {
+            columns,
+            rows_per_page,
+        }
+    }
+
+    /// Number of items on a full page.
+    pub fn page_size(&self) -> usize {
+        self.columns * self.rows_per_page
+    }
+
+    /// Number of pages needed for `len` items.
+    pub fn page_count(&self, len: usize) -> usize {
+        len.div_ceil(self.page_size()).max(1)
+    }
+
+    /// Page that the item at `index` is on.
+    pub fn page_of(&self, index: usize) -> usize {
+        index / self.page_size()
+    }
 
-const CARD_HEIGHT: u32 = 56;
+    /// Number of rows with items on the given page of `len` items.
+    pub fn page_rows(&self, len: usize, page: usize) -> usize {
+        len.div_ceil(self.columns)
+            .saturating_sub(page * self.rows_per_page)
+            .min(self.rows_per_page)
+    }
+}
 
-pub fn selector<'a, C: 'a, OnSelectFn>(
+#[allow(clippy::too_many_arguments)]
+pub fn selector<'a, C: 'a, OnSelectFn, OnPageFn>(
     title: &'a str,
     items: &'a [(usize, String, usize)],
+    layout: GridLayout,
+    page: usize,
+    focus: Option<usize>,
+    card_height: u32,
+    scheme: &'a ColorScheme,
+    fonts: &'a Fonts,
     on_select: OnSelectFn,
-) -> impl View<color::Color, C> + use<'a, C, OnSelectFn>
+    on_page: OnPageFn,
+) -> impl View<color::Color, C> + use<'a, C, OnSelectFn, OnPageFn>
 where
     OnSelectFn: Fn(&mut C, usize) + 'a + Copy,
+    OnPageFn: Fn(&mut C, usize) + 'a + Copy,
 {
-    ScrollView::new(
-        VStack::new((
-            Text::new(title, &*font::MONTSERRAT).with_font_size(font::SIZE_HEADING),
-            ForEach::<12>::new_vertical(
-                // It's a bit hacky, but it works.
-                // To split the categories into two columns, we tell buoyant that
-                // there are only half the number of categories, and then we index
-                // into the original list to get the correct category names.
-                &items[0..(items.len().div_ceil(2))],
-                move |(index, _, _): &(usize, String, usize)| {
-                    let category_name_1 = &items[*index * 2].1;
-                    // We're not guaranteed to have a second category if the number of categories is odd
-                    let category_name_2 = items.get(*index * 2 + 1).map(|x| &x.1);
-
-                    HStack::new((
-                        crate::view::ui::card::card(
-                            category_name_1,
-                            crate::view::ui::card::CardStyle::default(),
+    let page_count = layout.page_count(items.len());
+    let page = page.min(page_count - 1);
+    let first_row = page * layout.rows_per_page;
+    let page_rows = layout.page_rows(items.len(), page);
+
+    VStack::new((
+        HStack::new((
+            fonts.text(fonts.heading, title, move |title, font| {
+                Text::new(title, font).with_font_size(fonts.scale.heading)
+            }),
+            (page_count > 1).then(|| {
+                HStack::new((
+                    button::button(
+                        "Prev",
+                        ButtonStyle::new(scheme, fonts),
+                        move |state: &mut C| {
+                            on_page(state, page.saturating_sub(1));
+                        },
+                    ),
+                    Text::new(format!("{}/{}", page + 1, page_count), fonts.regular)
+                        .with_font_size(fonts.scale.caption),
+                    button::button(
+                        "Next",
+                        ButtonStyle::new(scheme, fonts),
+                        move |state: &mut C| {
+                            on_page(state, (page + 1).min(page_count - 1));
+                        },
+                    ),
+                ))
+                .with_spacing(spacing::ELEMENT)
+            }),
+        ))
+        .with_spacing(spacing::COMPONENT),
+        ForEach::<MAX_PAGE_ROWS>::new_vertical(&ROWS[..page_rows], move |row: &usize| {
+            let first = (first_row + *row) * layout.columns;
+            // Every row has the same number of cells so that the columns line
+            // up, with spacers filling out the last row
+            let cell = move |column: usize| {
+                let index = first + column;
+                match_view!((column < layout.columns, items.get(index)), {
+                    (true, Some((_, name, _))) => {
+                        card::card(
+                            name,
+                            CardStyle {
+                                focused: focus == Some(index),
+                                ..CardStyle::new(scheme, fonts)
+                            },
                             move |state: &mut C| {
-                                on_select(state, *index * 2);
+                                on_select(state, index);
                             },
                         )
                         .flex_frame()
-                        .with_ideal_height(CARD_HEIGHT),
-                        match_view!(category_name_2, {
-                            Some(category_name_2) => {
-                                let card_2 = crate::view::ui::card::card(
-                                    category_name_2,
-                                    crate::view::ui::card::CardStyle::default(),
-                                    move |state: &mut C| {
-                                        on_select(state, *index * 2 + 1);
-                                    },
-                                )
-                                .flex_frame()
-                                .with_ideal_height(CARD_HEIGHT);
-                                card_2
-                            },
-                            None => {
-                                EmptyView
-                            },
-                        }),
-                    ))
-                    .with_spacing(spacing::COMPONENT)
-                },
+                        .with_ideal_height(card_height)
+                    },
+                    (true, None) => Spacer::default(),
+                    (false, _) => EmptyView,
+                })
+            };
+            HStack::new((cell(0), cell(1), cell(2), cell(3))).with_spacing(spacing::COMPONENT)
+        })
+        .with_spacing(spacing::COMPONENT),
+    ))
+    .with_spacing(spacing::COMPONENT)
+    .with_alignment(HorizontalAlignment::Center)
+    .padding(Edges::All, spacing::SECTION_MARGIN)
+    .foreground_color(scheme.on_surface)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Items with the given label lengths, as built for the selector.
+    fn items(labels: &[&str]) -> Vec<(usize, String, usize)> {
+        labels
+            .iter()
+            .enumerate()
+            .map(|(index, label)| (index, label.to_string(), index))
+            .collect()
+    }
+
+    fn layout(columns: usize, rows_per_page: usize) -> GridLayout {
+        GridLayout {
+            columns,
+            rows_per_page,
+        }
+    }
+
+    #[test]
+    fn empty_grid_has_one_empty_page() {
+        let layout = layout(2, 3);
+        assert_eq!(layout.page_count(0), 1);
+        assert_eq!(layout.page_rows(0, 0), 0);
+    }
+
+    #[test]
+    fn exactly_full_grid_has_no_extra_page() {
+        let layout = layout(2, 3);
+        assert_eq!(layout.page_size(), 6);
+        assert_eq!(layout.page_count(6), 1);
+        assert_eq!(layout.page_rows(6, 0), 3);
+        assert_eq!(layout.page_of(5), 0);
+
+        // Two full pages, and then one more item spills onto a third
+        assert_eq!(layout.page_count(12), 2);
+        assert_eq!(layout.page_rows(12, 1), 3);
+        assert_eq!(layout.page_count(13), 3);
+        assert_eq!(layout.page_of(12), 2);
+        assert_eq!(layout.page_rows(13, 2), 1);
+    }
+
+    #[test]
+    fn partial_last_page() {
+        let layout = layout(3, 2);
+        assert_eq!(layout.page_count(8), 2);
+        assert_eq!(layout.page_rows(8, 0), 2);
+        assert_eq!(layout.page_rows(8, 1), 1);
+        // Pages past the end have no rows
+        assert_eq!(layout.page_rows(8, 2), 0);
+    }
+
+    fn fonts() -> Fonts {
+        Fonts::new(&crate::typography::Typography::default())
+    }
+
+    #[test]
+    fn columns_fit_label_width() {
+        assert_eq!(columns_to_fit(0.0), MAX_GRID_COLUMNS);
+        // Four columns of 108 pixel cards, each with 76 pixels for the label
+        assert_eq!(columns_to_fit(76.0), 4);
+        assert_eq!(columns_to_fit(77.0), 3);
+        assert_eq!(columns_to_fit(114.0), 3);
+        assert_eq!(columns_to_fit(192.0), 2);
+        assert_eq!(columns_to_fit(193.0), 1);
+        // Labels too wide even for one column still get one
+        assert_eq!(columns_to_fit(1000.0), 1);
+    }
+
+    #[test]
+    fn columns_follow_widest_label() {
+        let fonts = fonts();
+        let columns = |labels: &[&str]| {
+            GridLayout::new(&items(labels), None, 40, GRID_HEIGHT, &fonts).columns
+        };
+        assert_eq!(columns(&[]), MAX_GRID_COLUMNS);
+        assert_eq!(columns(&["Left", "Right"]), MAX_GRID_COLUMNS);
+        assert_eq!(
+            columns(&["Left elims rush to the center and back again"]),
+            1
+        );
+        // Wider labels never get more columns
+        let labels = [
+            "Left",
+            "Left AWP",
+            "Left elims rush",
+            "Left elims rush to center",
+        ];
+        for pair in labels.windows(2) {
+            assert!(columns(&pair[1..]) <= columns(&pair[..1]), "{pair:?}");
+        }
+        // Explicit columns are only limited to the most the grid supports
+        assert_eq!(
+            GridLayout::new(&items(&["Left"]), Some(9), 40, GRID_HEIGHT, &fonts).columns,
+            MAX_GRID_COLUMNS
+        );
+        assert_eq!(
+            GridLayout::new(
+                &items(&["Left elims rush to center"]),
+                Some(4),
+                40,
+                GRID_HEIGHT,
+                &fonts
             )
-            .with_spacing(spacing::COMPONENT),
-        ))
-        .with_spacing(spacing::COMPONENT)
-        .with_alignment(HorizontalAlignment::Center)
-        .padding(Edges::All, spacing::SECTION_MARGIN)
-        .foreground_color(color::Color::WHITE),
-    )
-    .with_direction(ScrollDirection::Vertical)
-    .with_overlapping_bar(true)
-    .with_bar_visibility(if items.len() > 4 {
-        buoyant::view::scroll_view::ScrollBarVisibility::Always
-    } else {
-        buoyant::view::scroll_view::ScrollBarVisibility::Never
-    })
+            .columns,
+            4
+        );
+    }
+
+    #[test]
+    fn rows_fit_height() {
+        let fonts = fonts();
+        let layout = GridLayout::new(&items(&["Left"]), None, 40, GRID_HEIGHT, &fonts);
+        let rows = layout.rows_per_page as u32;
+        assert!(rows * 40 + (rows - 1) * spacing::COMPONENT <= GRID_HEIGHT);
+        // A card taller than the grid still gets one row
+        assert_eq!(
+            GridLayout::new(
+                &items(&["Left"]),
+                None,
+                GRID_HEIGHT * 2,
+                GRID_HEIGHT,
+                &fonts
+            )
+            .rows_per_page,
+            1
+        );
+    }
 }
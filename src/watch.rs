@@ -0,0 +1,78 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use crate::{ExternalState, RouteInfo};
+
+/// How often [`SelectionWatch::changed`] checks for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Snapshot of the selector's selection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    /// The selected route
+    pub route: RouteInfo,
+    /// Whether the route has been confirmed
+    pub confirmed: bool,
+}
+
+/// Watch-style handle for observing selection changes, created by
+/// [`DoxaSelect::watch`].
+///
+/// # Example
+///
+/// ```ignore
+/// let mut watch = selector.watch();
+/// vexide::task::spawn(async move {
+///     loop {
+///         let selection = watch.changed().await;
+///         println!("Selected {}", selection.route.name);
+///     }
+/// })
+/// .detach();
+/// ```
+///
+/// [`DoxaSelect::watch`]: crate::DoxaSelect::watch
+pub struct SelectionWatch {
+    state: Rc<RefCell<ExternalState>>,
+    routes: Rc<[RouteInfo]>,
+    /// (selection, confirmed) as of the last time the selection was observed
+    seen: (usize, bool),
+}
+
+impl SelectionWatch {
+    pub(crate) fn new(state: Rc<RefCell<ExternalState>>, routes: Rc<[RouteInfo]>) -> Self {
+        let seen = Self::key(&state.borrow());
+        Self {
+            state,
+            routes,
+            seen,
+        }
+    }
+
+    fn key(state: &ExternalState) -> (usize, bool) {
+        (state.selection, state.confirmed)
+    }
+
+    /// Returns the current selection and marks it as seen.
+    pub fn current(&mut self) -> Selection {
+        let (selection, confirmed) = Self::key(&self.state.borrow());
+        self.seen = (selection, confirmed);
+        Selection {
+            route: self.routes[selection].clone(),
+            confirmed,
+        }
+    }
+
+    /// Returns whether the selection has changed since it was last seen.
+    pub fn has_changed(&self) -> bool {
+        Self::key(&self.state.borrow()) != self.seen
+    }
+
+    /// Waits until the selected route or its confirmation state changes, then
+    /// returns the new selection.
+    pub async fn changed(&mut self) -> Selection {
+        while !self.has_changed() {
+            vexide::time::sleep(POLL_INTERVAL).await;
+        }
+        self.current()
+    }
+}